@@ -0,0 +1,999 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::bus::Bus;
+use crate::cpu::CpuVariant;
+use crate::instruction::{AddrMode, Instruction, INSTRUCTIONS, INSTRUCTIONS_65C02};
+
+// A two-pass assembler for the usual 6502 source format:
+//
+//         * = $C000          ; or .org $C000
+//  COUNT  = 8                ; constants
+//  start: LDX #COUNT
+//  @loop  LDA table,X        ; @labels are local to the last global label
+//         STA $0200,X
+//         DEX
+//         BNE @loop
+//         JMP (vector)
+//  table  .byte <start, >start, "text", 'c'
+//  vector .word start
+//
+// Labels at the start of a line may leave out the colon. Numbers are $hex,
+// %binary, decimal or 'c'. Expressions take + - * / & | ^, parentheses,
+// unary -, ~, and < and > for the low and high byte. A lone * is the
+// address of the current line.
+//
+// The first pass works out the size of every line. An operand that is not
+// known yet is taken to be absolute, so a zero page variable has to be
+// defined before its first use to get the short form.
+//
+// assemble() takes the NMOS instruction set, assemble_for() that of any of
+// the variants, so BRA, STZ and ($zp) need the 65C02. BBRn and BBSn take
+// the zero page address and the branch target: BBS3 flags,done.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AsmError
+{
+    pub line: usize, // 1 based
+    pub message: String,
+}
+
+impl fmt::Display for AsmError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        return write!(f, "line {}: {}", self.line, self.message);
+    }
+}
+
+impl std::error::Error for AsmError {}
+
+// The output of a successful assembly. Every origin in the source starts a
+// new segment.
+#[derive(Debug, Clone, Default)]
+pub struct Assembly
+{
+    pub segments: Vec<(u16, Vec<u8>)>,
+    pub symbols: HashMap<String, u16>,
+}
+
+impl Assembly
+{
+    // one flat image from the lowest to the highest address written, with
+    // any gaps between segments filled with $00. Returns the start address.
+    pub fn image(&self) -> (u16, Vec<u8>)
+    {
+        let used = self.segments.iter().filter(|(_, bytes)| !bytes.is_empty());
+
+        let start : u32 = used.clone().map(|(org, _)| *org as u32).min().unwrap_or(0);
+        let end : u32 = used.clone().map(|(org, bytes)| *org as u32 + bytes.len() as u32).max().unwrap_or(0);
+
+        let mut image : Vec<u8> = vec![0x00; (end - start) as usize];
+        for (org, bytes) in used
+        {
+            let offset : usize = (*org as u32 - start) as usize;
+            image[offset..offset + bytes.len()].copy_from_slice(bytes);
+        }
+
+        return (start as u16, image);
+    }
+
+    // write every segment out over the bus.
+    pub fn load(&self, bus: &mut dyn Bus)
+    {
+        for (org, bytes) in self.segments.iter()
+        {
+            for (i, byte) in bytes.iter().enumerate()
+            {
+                bus.write(org.wrapping_add(i as u16), *byte);
+            }
+        }
+    }
+
+    pub fn symbol(&self, name: &str) -> Option<u16>
+    {
+        return self.symbols.get(name).copied();
+    }
+}
+
+pub fn assemble(source: &str) -> Result<Assembly, AsmError>
+{
+    return assemble_for(CpuVariant::Nmos6502, source);
+}
+
+pub fn assemble_for(variant: CpuVariant, source: &str) -> Result<Assembly, AsmError>
+{
+    let table : &[Instruction; 0x100] = variant.instructions();
+    let statements : Vec<Statement> = parse(source)?;
+
+    // pass 1, place every label and pick an opcode for every instruction.
+    let mut symbols : HashMap<String, i64> = HashMap::new();
+    let mut opcodes : Vec<u8> = Vec::new();
+    let mut pc : i64 = 0;
+
+    for stmt in statements.iter()
+    {
+        let err = |message: String| AsmError { line: stmt.line, message };
+
+        if let Some(label) = &stmt.label
+        {
+            define(&mut symbols, label, pc).map_err(err)?;
+        }
+
+        match &stmt.kind
+        {
+            Kind::Empty => {}
+            Kind::Org(expr) => pc = origin(eval(expr, &symbols, pc).map_err(err)?).map_err(err)?,
+            Kind::Equ(name, expr) =>
+            {
+                let value : i64 = eval(expr, &symbols, pc).map_err(err)?;
+                define(&mut symbols, name, value).map_err(err)?;
+            }
+            Kind::Bytes(items) => pc += items.iter().map(|item| item.len()).sum::<i64>(),
+            Kind::Words(exprs) => pc += 2 * exprs.len() as i64,
+            Kind::Op(mnemonic, operand) =>
+            {
+                let opcode : u8 = select(table, mnemonic, operand, &symbols, pc).map_err(err)?;
+                opcodes.push(opcode);
+                pc += table[opcode as usize].length() as i64;
+            }
+        }
+
+        if pc > 0x10000
+        {
+            return Err(err(String::from("code runs past $FFFF")));
+        }
+    }
+
+    // pass 2, every symbol is known now, emit the bytes.
+    let mut out : Assembly = Assembly::default();
+    let mut opcodes = opcodes.into_iter();
+    let mut pc : i64 = 0;
+
+    out.segments.push((0x0000, Vec::new()));
+
+    for stmt in statements.iter()
+    {
+        let err = |message: String| AsmError { line: stmt.line, message };
+        let mut bytes : Vec<u8> = Vec::new();
+
+        match &stmt.kind
+        {
+            Kind::Empty | Kind::Equ(..) => {}
+            Kind::Org(expr) =>
+            {
+                pc = origin(eval(expr, &symbols, pc).map_err(err)?).map_err(err)?;
+                out.segments.push((pc as u16, Vec::new()));
+            }
+            Kind::Bytes(items) =>
+            {
+                for item in items
+                {
+                    match item
+                    {
+                        Item::Text(text) => bytes.extend_from_slice(text.as_bytes()),
+                        Item::Expr(expr) => bytes.push(byte(eval(expr, &symbols, pc).map_err(err)?).map_err(err)?),
+                    }
+                }
+            }
+            Kind::Words(exprs) =>
+            {
+                for expr in exprs
+                {
+                    let value : u16 = word(eval(expr, &symbols, pc).map_err(err)?).map_err(err)?;
+                    bytes.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+            Kind::Op(_, operand) =>
+            {
+                let opcode : u8 = opcodes.next().unwrap_or_default();
+                bytes = encode(table, opcode, operand, &symbols, pc).map_err(err)?;
+            }
+        }
+
+        pc += bytes.len() as i64;
+        if let Some((_, segment)) = out.segments.last_mut()
+        {
+            segment.extend_from_slice(&bytes);
+        }
+    }
+
+    out.segments.retain(|(_, bytes)| !bytes.is_empty());
+    out.symbols = symbols.into_iter().map(|(name, value)| (name, value as u16)).collect();
+
+    return Ok(out);
+}
+
+// pick the opcode for a mnemonic and operand. The operand syntax narrows it
+// down to a few modes, the first one the instruction has wins.
+fn select(table: &[Instruction; 0x100], mnemonic: &str, operand: &Operand, symbols: &HashMap<String, i64>, pc: i64) -> Result<u8, String>
+{
+    let fits = |expr: &Expr| matches!(eval(expr, symbols, pc), Ok(value) if (0..=0xFF).contains(&value));
+
+    let modes : Vec<AddrMode> = match operand
+    {
+        Operand::None => vec![AddrMode::IMP, AddrMode::A, AddrMode::JAM],
+        Operand::Acc => vec![AddrMode::A],
+        Operand::Imm(_) => vec![AddrMode::IMM],
+        Operand::Ind(expr) if fits(expr) => vec![AddrMode::ZpgInd, AddrMode::IND],
+        Operand::Ind(_) => vec![AddrMode::IND, AddrMode::ZpgInd],
+        Operand::IndX(expr) if fits(expr) => vec![AddrMode::IndX, AddrMode::AbsIndX],
+        Operand::IndX(_) => vec![AddrMode::AbsIndX, AddrMode::IndX],
+        Operand::IndY(_) => vec![AddrMode::IndY],
+        Operand::ZpRel(..) => vec![AddrMode::ZpgRel],
+        Operand::Direct(expr) if fits(expr) => vec![AddrMode::REL, AddrMode::ZPG, AddrMode::ABS],
+        Operand::Direct(_) => vec![AddrMode::REL, AddrMode::ABS, AddrMode::ZPG],
+        Operand::IndexX(expr) if fits(expr) => vec![AddrMode::ZpgX, AddrMode::AbsX],
+        Operand::IndexX(_) => vec![AddrMode::AbsX, AddrMode::ZpgX],
+        Operand::IndexY(expr) if fits(expr) => vec![AddrMode::ZpgY, AddrMode::AbsY],
+        Operand::IndexY(_) => vec![AddrMode::AbsY, AddrMode::ZpgY],
+    };
+
+    for mode in modes
+    {
+        if let Some(opcode) = find_opcode(table, mnemonic, mode)
+        {
+            return Ok(opcode);
+        }
+    }
+
+    if table.iter().any(|inst| inst.mnemonic() == mnemonic)
+    {
+        return Err(format!("{} does not take that operand", mnemonic));
+    }
+    return Err(format!("unknown instruction {}", mnemonic));
+}
+
+// the documented opcode when there is one, so NOP is $EA and SBC # is $E9.
+fn find_opcode(table: &[Instruction; 0x100], mnemonic: &str, mode: AddrMode) -> Option<u8>
+{
+    let mut found : Option<u8> = None;
+
+    for (opcode, inst) in table.iter().enumerate()
+    {
+        if inst.mode == mode && inst.mnemonic() == mnemonic
+        {
+            if inst.documented
+            {
+                return Some(opcode as u8);
+            }
+            found = found.or(Some(opcode as u8));
+        }
+    }
+
+    return found;
+}
+
+// a mnemonic of any variant, the parser does not know which one is wanted.
+fn find_opcode_any(mnemonic: &str) -> bool
+{
+    return INSTRUCTIONS.iter().chain(INSTRUCTIONS_65C02.iter()).any(|inst| inst.mnemonic() == mnemonic);
+}
+
+fn encode(table: &[Instruction; 0x100], opcode: u8, operand: &Operand, symbols: &HashMap<String, i64>, pc: i64) -> Result<Vec<u8>, String>
+{
+    let mode : AddrMode = table[opcode as usize].mode;
+    let mut bytes : Vec<u8> = vec![opcode];
+
+    let value : i64 = match operand
+    {
+        Operand::None | Operand::Acc => return Ok(bytes),
+        Operand::Imm(expr) | Operand::Direct(expr) | Operand::IndexX(expr) | Operand::IndexY(expr)
+            | Operand::Ind(expr) | Operand::IndX(expr) | Operand::IndY(expr) => eval(expr, symbols, pc)?,
+        Operand::ZpRel(zp, expr) =>
+        {
+            let addr : i64 = eval(zp, symbols, pc)?;
+            if !(0..=0xFF).contains(&addr)
+            {
+                return Err(format!("${:X} is not a zero page address", addr));
+            }
+            bytes.push(addr as u8);
+            eval(expr, symbols, pc)?
+        }
+    };
+
+    match mode
+    {
+        AddrMode::IMM => bytes.push(byte(value)?),
+        AddrMode::REL | AddrMode::ZpgRel =>
+        {
+            // the offset counts from the end of the instruction.
+            let offset : i64 = value - (pc + bytes.len() as i64 + 1);
+            if !(-128..=127).contains(&offset)
+            {
+                return Err(format!("branch to ${:04X} is out of range", value));
+            }
+            bytes.push(offset as u8);
+        }
+        AddrMode::ZPG | AddrMode::ZpgX | AddrMode::ZpgY | AddrMode::IndX | AddrMode::IndY | AddrMode::ZpgInd =>
+        {
+            if !(0..=0xFF).contains(&value)
+            {
+                return Err(format!("${:X} is not a zero page address", value));
+            }
+            bytes.push(value as u8);
+        }
+        AddrMode::ABS | AddrMode::AbsX | AddrMode::AbsY | AddrMode::IND | AddrMode::AbsIndX =>
+        {
+            if !(0..=0xFFFF).contains(&value)
+            {
+                return Err(format!("${:X} is not an address", value));
+            }
+            bytes.extend_from_slice(&(value as u16).to_le_bytes());
+        }
+        AddrMode::A | AddrMode::IMP | AddrMode::JAM => {}
+    }
+
+    return Ok(bytes);
+}
+
+// a byte operand may be given signed, -1 is $FF.
+fn byte(value: i64) -> Result<u8, String>
+{
+    if !(-128..=0xFF).contains(&value)
+    {
+        return Err(format!("{} does not fit in a byte", value));
+    }
+    return Ok(value as u8);
+}
+
+fn word(value: i64) -> Result<u16, String>
+{
+    if !(-0x8000..=0xFFFF).contains(&value)
+    {
+        return Err(format!("{} does not fit in a word", value));
+    }
+    return Ok(value as u16);
+}
+
+fn origin(value: i64) -> Result<i64, String>
+{
+    if !(0..=0xFFFF).contains(&value)
+    {
+        return Err(format!("origin {} is outside $0000-$FFFF", value));
+    }
+    return Ok(value);
+}
+
+fn define(symbols: &mut HashMap<String, i64>, name: &str, value: i64) -> Result<(), String>
+{
+    if symbols.insert(String::from(name), value).is_some()
+    {
+        return Err(format!("{} is defined twice", name));
+    }
+    return Ok(());
+}
+
+// ---- source parsing ----
+
+struct Statement
+{
+    line: usize,
+    label: Option<String>,
+    kind: Kind,
+}
+
+enum Kind
+{
+    Empty,
+    Org(Expr),
+    Equ(String, Expr),
+    Bytes(Vec<Item>),
+    Words(Vec<Expr>),
+    Op(String, Operand),
+}
+
+enum Item
+{
+    Expr(Expr),
+    Text(String),
+}
+
+impl Item
+{
+    fn len(&self) -> i64
+    {
+        return match self
+        {
+            Item::Expr(_) => 1,
+            Item::Text(text) => text.len() as i64,
+        };
+    }
+}
+
+enum Operand
+{
+    None,
+    Acc,          // A
+    Imm(Expr),    // #expr
+    Direct(Expr), // expr, zero page, absolute or a branch target
+    IndexX(Expr), // expr,X
+    IndexY(Expr), // expr,Y
+    Ind(Expr),    // (expr)
+    IndX(Expr),   // (expr,X)
+    IndY(Expr),   // (expr),Y
+    ZpRel(Expr, Expr), // zp,target for BBR and BBS
+}
+
+enum Expr
+{
+    Num(i64),
+    Sym(String),
+    Pc,
+    Unary(char, Box<Expr>),
+    Binary(char, Box<Expr>, Box<Expr>),
+}
+
+fn eval(expr: &Expr, symbols: &HashMap<String, i64>, pc: i64) -> Result<i64, String>
+{
+    return match expr
+    {
+        Expr::Num(value) => Ok(*value),
+        Expr::Pc => Ok(pc),
+        Expr::Sym(name) => symbols.get(name).copied().ok_or(format!("{} is not defined", name)),
+        Expr::Unary(op, inner) =>
+        {
+            let value : i64 = eval(inner, symbols, pc)?;
+            match op
+            {
+                '-' => value.checked_neg().ok_or(String::from("arithmetic overflow")),
+                '~' => Ok(!value & 0xFFFF),
+                '<' => Ok(value & 0xFF),
+                _ => Ok((value >> 8) & 0xFF),
+            }
+        }
+        Expr::Binary(op, lhs, rhs) =>
+        {
+            let a : i64 = eval(lhs, symbols, pc)?;
+            let b : i64 = eval(rhs, symbols, pc)?;
+            let value : Option<i64> = match op
+            {
+                '+' => a.checked_add(b),
+                '-' => a.checked_sub(b),
+                '*' => a.checked_mul(b),
+                '/' if b == 0 => return Err(String::from("division by zero")),
+                '/' => a.checked_div(b),
+                '&' => Some(a & b),
+                '|' => Some(a | b),
+                _ => Some(a ^ b),
+            };
+            value.ok_or(String::from("arithmetic overflow"))
+        }
+    };
+}
+
+fn parse(source: &str) -> Result<Vec<Statement>, AsmError>
+{
+    let mut statements : Vec<Statement> = Vec::new();
+    let mut scope : String = String::new();
+
+    for (index, text) in source.lines().enumerate()
+    {
+        let line : usize = index + 1;
+        let stmt : Statement = parse_line(strip_comment(text), line, &mut scope)
+            .map_err(|message| AsmError { line, message })?;
+        statements.push(stmt);
+    }
+
+    return Ok(statements);
+}
+
+fn strip_comment(text: &str) -> &str
+{
+    let mut quote : Option<char> = None;
+
+    for (i, c) in text.char_indices()
+    {
+        match quote
+        {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == ';' => return &text[..i],
+            None => {}
+        }
+    }
+
+    return text;
+}
+
+fn parse_line(text: &str, line: usize, scope: &mut String) -> Result<Statement, String>
+{
+    let mut stmt : Statement = Statement { line, label: None, kind: Kind::Empty };
+    let at_column_0 : bool = !text.starts_with(char::is_whitespace);
+    let mut rest : &str = text.trim();
+
+    if rest.is_empty()
+    {
+        return Ok(stmt);
+    }
+
+    // * = expr
+    if let Some(after) = rest.strip_prefix('*')
+    {
+        let after : &str = after.trim_start();
+        let expr : &str = after.strip_prefix('=').ok_or("expected = after *")?;
+        stmt.kind = Kind::Org(parse_expr_text(expr, scope)?);
+        return Ok(stmt);
+    }
+
+    let (word, after) = split_word(rest);
+
+    if let Some(expr) = after.strip_prefix('=')
+    {
+        stmt.kind = Kind::Equ(label_name(word, scope)?, parse_expr_text(expr, scope)?);
+        return Ok(stmt);
+    }
+
+    let label : Option<&str> = match word.strip_suffix(':')
+    {
+        Some(name) => Some(name),
+        None if at_column_0 && !is_keyword(word) => Some(word),
+        None => None,
+    };
+
+    if let Some(name) = label
+    {
+        let full : String = label_name(name, scope)?;
+        if !name.starts_with('@')
+        {
+            *scope = full.clone();
+        }
+        stmt.label = Some(full);
+        rest = after;
+    }
+
+    if rest.is_empty()
+    {
+        return Ok(stmt);
+    }
+
+    let (word, operand) = split_word(rest);
+    let keyword : String = word.to_ascii_uppercase();
+
+    stmt.kind = match keyword.as_str()
+    {
+        ".ORG" => Kind::Org(parse_expr_text(operand, scope)?),
+        ".BYTE" =>
+        {
+            let mut items : Vec<Item> = Vec::new();
+            for tokens in split_commas(&lex(operand, scope)?)
+            {
+                match tokens
+                {
+                    [Token::Str(text)] => items.push(Item::Text(text.clone())),
+                    _ => items.push(Item::Expr(parse_expr_tokens(tokens)?)),
+                }
+            }
+            Kind::Bytes(items)
+        }
+        ".WORD" =>
+        {
+            let mut exprs : Vec<Expr> = Vec::new();
+            for tokens in split_commas(&lex(operand, scope)?)
+            {
+                exprs.push(parse_expr_tokens(tokens)?);
+            }
+            Kind::Words(exprs)
+        }
+        _ if keyword.starts_with('.') => return Err(format!("unknown directive {}", word)),
+        _ if find_opcode_any(&keyword) => Kind::Op(keyword, parse_operand(operand, scope)?),
+        _ => return Err(format!("unknown instruction {}", word)),
+    };
+
+    return Ok(stmt);
+}
+
+fn split_word(text: &str) -> (&str, &str)
+{
+    let end : usize = text.find(char::is_whitespace).unwrap_or(text.len());
+
+    // a label can be glued to what follows it, as in "loop:DEX".
+    if let Some(colon) = text[..end].find(':')
+    {
+        return (&text[..colon + 1], text[colon + 1..].trim_start());
+    }
+    return (&text[..end], text[end..].trim_start());
+}
+
+fn is_keyword(word: &str) -> bool
+{
+    return word.starts_with('.') || find_opcode_any(&word.to_ascii_uppercase());
+}
+
+// local labels are kept as global@local so each global label gets its own set.
+fn label_name(name: &str, scope: &str) -> Result<String, String>
+{
+    let valid : bool = name.trim_start_matches('@').starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name[1..].chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if !valid
+    {
+        return Err(format!("bad label {}", name));
+    }
+
+    if name.starts_with('@')
+    {
+        return Ok(format!("{}{}", scope, name));
+    }
+    return Ok(String::from(name));
+}
+
+fn parse_operand(text: &str, scope: &str) -> Result<Operand, String>
+{
+    let tokens : Vec<Token> = lex(text, scope)?;
+
+    match tokens.as_slice()
+    {
+        [] => return Ok(Operand::None),
+        [Token::Ident(name)] if name.eq_ignore_ascii_case("A") => return Ok(Operand::Acc),
+        [Token::Op('#'), rest @ ..] => return Ok(Operand::Imm(parse_expr_tokens(rest)?)),
+        _ => {}
+    }
+
+    // (expr,X)  (expr),Y  (expr), otherwise the parentheses just group.
+    if let [Token::LParen, inner @ ..] = tokens.as_slice()
+    {
+        let mut parser : Parser = Parser { tokens: inner, pos: 0 };
+        let expr : Expr = parser.expr()?;
+
+        match &inner[parser.pos..]
+        {
+            [Token::Comma, Token::Ident(x), Token::RParen] if x.eq_ignore_ascii_case("X") => return Ok(Operand::IndX(expr)),
+            [Token::RParen, Token::Comma, Token::Ident(y)] if y.eq_ignore_ascii_case("Y") => return Ok(Operand::IndY(expr)),
+            [Token::RParen] => return Ok(Operand::Ind(expr)),
+            _ => {}
+        }
+    }
+
+    match tokens.as_slice()
+    {
+        [rest @ .., Token::Comma, Token::Ident(x)] if x.eq_ignore_ascii_case("X") => return Ok(Operand::IndexX(parse_expr_tokens(rest)?)),
+        [rest @ .., Token::Comma, Token::Ident(y)] if y.eq_ignore_ascii_case("Y") => return Ok(Operand::IndexY(parse_expr_tokens(rest)?)),
+        _ => {}
+    }
+
+    return match split_commas(&tokens).as_slice()
+    {
+        [zp, target] => Ok(Operand::ZpRel(parse_expr_tokens(zp)?, parse_expr_tokens(target)?)),
+        _ => Ok(Operand::Direct(parse_expr_tokens(&tokens)?)),
+    };
+}
+
+fn parse_expr_text(text: &str, scope: &str) -> Result<Expr, String>
+{
+    return parse_expr_tokens(&lex(text, scope)?);
+}
+
+fn parse_expr_tokens(tokens: &[Token]) -> Result<Expr, String>
+{
+    let mut parser : Parser = Parser { tokens, pos: 0 };
+    let expr : Expr = parser.expr()?;
+
+    if parser.pos != tokens.len()
+    {
+        return Err(String::from("junk after expression"));
+    }
+    return Ok(expr);
+}
+
+fn split_commas(tokens: &[Token]) -> Vec<&[Token]>
+{
+    if tokens.is_empty()
+    {
+        return Vec::new();
+    }
+    return tokens.split(|t| *t == Token::Comma).collect();
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token
+{
+    Num(i64),
+    Ident(String),
+    Str(String),
+    Op(char),
+    LParen,
+    RParen,
+    Comma,
+}
+
+fn lex(text: &str, scope: &str) -> Result<Vec<Token>, String>
+{
+    let chars : Vec<char> = text.chars().collect();
+    let mut tokens : Vec<Token> = Vec::new();
+    let mut i : usize = 0;
+
+    let take = |i: &mut usize, ok: &dyn Fn(char) -> bool| -> String
+    {
+        let start : usize = *i;
+        while *i < chars.len() && ok(chars[*i])
+        {
+            *i += 1;
+        }
+        return chars[start..*i].iter().collect();
+    };
+
+    while i < chars.len()
+    {
+        let c : char = chars[i];
+
+        if c.is_whitespace()
+        {
+            i += 1;
+        }
+        else if c == '$' || (c == '%' && i + 1 < chars.len() && matches!(chars[i + 1], '0' | '1'))
+        {
+            i += 1;
+            let radix : u32 = if c == '$' { 16 } else { 2 };
+            let digits : String = take(&mut i, &|c| c.is_digit(radix));
+            let value : i64 = i64::from_str_radix(&digits, radix).map_err(|_| format!("bad number {}{}", c, digits))?;
+            tokens.push(Token::Num(value));
+        }
+        else if c.is_ascii_digit()
+        {
+            let digits : String = take(&mut i, &|c| c.is_ascii_digit());
+            tokens.push(Token::Num(digits.parse::<i64>().map_err(|_| format!("bad number {}", digits))?));
+        }
+        else if c == '\''
+        {
+            if i + 2 >= chars.len() || chars[i + 2] != '\''
+            {
+                return Err(String::from("bad character constant"));
+            }
+            tokens.push(Token::Num(chars[i + 1] as i64));
+            i += 3;
+        }
+        else if c == '"'
+        {
+            i += 1;
+            let body : String = take(&mut i, &|c| c != '"');
+            if i >= chars.len()
+            {
+                return Err(String::from("unterminated string"));
+            }
+            i += 1;
+            tokens.push(Token::Str(body));
+        }
+        else if c.is_ascii_alphabetic() || c == '_' || c == '@'
+        {
+            i += 1;
+            let name : String = format!("{}{}", c, take(&mut i, &|c| c.is_ascii_alphanumeric() || c == '_'));
+            if name.starts_with('@')
+            {
+                tokens.push(Token::Ident(format!("{}{}", scope, name)));
+            }
+            else
+            {
+                tokens.push(Token::Ident(name));
+            }
+        }
+        else
+        {
+            i += 1;
+            tokens.push(match c
+            {
+                '(' => Token::LParen,
+                ')' => Token::RParen,
+                ',' => Token::Comma,
+                '+' | '-' | '*' | '/' | '&' | '|' | '^' | '~' | '<' | '>' | '#' => Token::Op(c),
+                _ => return Err(format!("unexpected {}", c)),
+            });
+        }
+    }
+
+    return Ok(tokens);
+}
+
+// precedence climbing, loosest first: | ^ &, then + -, then * /.
+struct Parser<'a>
+{
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl Parser<'_>
+{
+    fn peek(&self) -> Option<&Token>
+    {
+        return self.tokens.get(self.pos);
+    }
+
+    fn expr(&mut self) -> Result<Expr, String>
+    {
+        return self.binary(0);
+    }
+
+    fn binary(&mut self, level: usize) -> Result<Expr, String>
+    {
+        const LEVELS : [&str; 3] = ["|^&", "+-", "*/"];
+
+        if level == LEVELS.len()
+        {
+            return self.unary();
+        }
+
+        let mut lhs : Expr = self.binary(level + 1)?;
+
+        while let Some(Token::Op(op)) = self.peek()
+        {
+            let op : char = *op;
+            if !LEVELS[level].contains(op)
+            {
+                break;
+            }
+            self.pos += 1;
+            let rhs : Expr = self.binary(level + 1)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+
+        return Ok(lhs);
+    }
+
+    fn unary(&mut self) -> Result<Expr, String>
+    {
+        let token : Option<Token> = self.peek().cloned();
+        self.pos += 1;
+
+        return match token
+        {
+            Some(Token::Op(op)) if "-~<>".contains(op) => Ok(Expr::Unary(op, Box::new(self.unary()?))),
+            Some(Token::Op('*')) => Ok(Expr::Pc),
+            Some(Token::Num(value)) => Ok(Expr::Num(value)),
+            Some(Token::Ident(name)) => Ok(Expr::Sym(name)),
+            Some(Token::LParen) =>
+            {
+                let inner : Expr = self.expr()?;
+                if self.peek() != Some(&Token::RParen)
+                {
+                    return Err(String::from("missing )"));
+                }
+                self.pos += 1;
+                Ok(inner)
+            }
+            Some(token) => Err(format!("unexpected {:?} in expression", token)),
+            None => Err(String::from("missing operand")),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::disasm::{decode_for, Line};
+
+    fn bytes(source: &str) -> Vec<u8>
+    {
+        return assemble(source).unwrap_or_else(|err| panic!("{}", err)).image().1;
+    }
+
+    fn error(source: &str) -> AsmError
+    {
+        return assemble(source).expect_err("assembled");
+    }
+
+    #[test]
+    fn instructions()
+    {
+        let assembly : Assembly = assemble("* = $C000\n LDA #$42\n STA $0200\n LDX $10\n ASL A\n RTS\n").unwrap();
+        assert_eq!(assembly.image(), (0xC000, vec![0xA9, 0x42, 0x8D, 0x00, 0x02, 0xA6, 0x10, 0x0A, 0x60]));
+    }
+
+    #[test]
+    fn expressions()
+    {
+        assert_eq!(bytes(".byte 2+3*4, (2+3)*4, 8-2-1, 20/3"), vec![14, 20, 5, 6]);
+        assert_eq!(bytes(".byte 1|6&3, 5^1, -1, ~0 & $FF"), vec![3, 4, 0xFF, 0xFF]);
+        assert_eq!(bytes(".byte <$1234, >$1234, %101, 'A'"), vec![0x34, 0x12, 0x05, 0x41]);
+        assert_eq!(bytes(" LDA #(2+3)*4"), vec![0xA9, 20]);
+    }
+
+    #[test]
+    fn current_address()
+    {
+        assert_eq!(bytes("* = $1000\n .word *, *+2\n JMP *\n"), vec![0x00, 0x10, 0x02, 0x10, 0x4C, 0x04, 0x10]);
+    }
+
+    #[test]
+    fn strings()
+    {
+        assert_eq!(bytes(" .byte \"Hi\", 0, 'x'"), vec![0x48, 0x69, 0x00, 0x78]);
+        assert_eq!(bytes(" .byte \"a;b\" ; not part of the string"), vec![0x61, 0x3B, 0x62]);
+    }
+
+    // each global label gets its own @loop.
+    #[test]
+    fn local_labels()
+    {
+        let source : &str = "
+* = $0300
+first   LDX #2
+@loop   DEX
+        BNE @loop
+second: LDY #2
+@loop   DEY
+        BNE @loop
+";
+        let assembly : Assembly = assemble(source).unwrap();
+        assert_eq!(assembly.image().1, vec![0xA2, 0x02, 0xCA, 0xD0, 0xFD, 0xA0, 0x02, 0x88, 0xD0, 0xFD]);
+        assert_eq!(assembly.symbol("first@loop"), Some(0x0302));
+        assert_eq!(assembly.symbol("second@loop"), Some(0x0307));
+    }
+
+    // a symbol not known yet in pass 1 gets the absolute form even when it
+    // turns out to be in zero page.
+    #[test]
+    fn forward_references()
+    {
+        let source : &str = "
+* = $0400
+        LDA zp
+        LDA later
+zp = $10
+        LDA zp
+later   RTS
+";
+        assert_eq!(bytes(source), vec![0xAD, 0x10, 0x00, 0xAD, 0x08, 0x04, 0xA5, 0x10, 0x60]);
+    }
+
+    #[test]
+    fn errors()
+    {
+        let err : AsmError = error("* = $0000\n BNE far\n* = $0100\nfar RTS\n");
+        assert_eq!(err, AsmError { line: 2, message: String::from("branch to $0100 is out of range") });
+
+        assert_eq!(error(" NOP\n FOO\n").line, 2);
+        assert_eq!(error(" NOP\n FOO\n").message, "unknown instruction FOO");
+        assert_eq!(error(" LDA nowhere\n").message, "nowhere is not defined");
+        assert_eq!(error("here NOP\nhere NOP\n").message, "here is defined twice");
+        assert_eq!(error(" LDX ($10),Y\n").message, "LDX does not take that operand");
+        assert_eq!(error(" LDA #$100\n").message, "256 does not fit in a byte");
+        assert_eq!(error(" .byte \"open\n").message, "unterminated string");
+        assert_eq!(error(" .byte $7FFFFFFFFFFFFFFF+1\n").message, "arithmetic overflow");
+        assert_eq!(error(" .word 99999999999*99999999999\n").message, "arithmetic overflow");
+        assert_eq!(error(" .byte -(-$7FFFFFFFFFFFFFFF-1)\n").message, "arithmetic overflow");
+        assert_eq!(error("* = -1\n NOP\n").message, "origin -1 is outside $0000-$FFFF");
+        assert_eq!(error("* = $10000\n").message, "origin 65536 is outside $0000-$FFFF");
+    }
+
+    #[test]
+    fn cmos_instructions()
+    {
+        assert_eq!(error(" BRA *\n").message, "unknown instruction BRA");
+
+        let source : &str = "* = $0200\n STZ $10\n LDA ($12)\n JMP ($1234,X)\n BBS3 $10,*\n";
+        let assembly : Assembly = assemble_for(CpuVariant::Wdc65C02, source).unwrap();
+        assert_eq!(assembly.image().1, vec![0x64, 0x10, 0xB2, 0x12, 0x7C, 0x34, 0x12, 0xBF, 0x10, 0xFD]);
+    }
+
+    // whatever the disassembler prints assembles back to the same text, and
+    // to the same bytes for the documented opcodes. The undocumented ones
+    // may come back as the documented opcode of the same name.
+    #[test]
+    fn disassembler_round_trip()
+    {
+        for variant in [CpuVariant::Nmos6502, CpuVariant::Wdc65C02]
+        {
+            for (opcode, inst) in variant.instructions().iter().enumerate()
+            {
+                let input : [u8; 3] = [opcode as u8, 0x12, 0x34];
+                let line : Line = decode_for(variant, &input, 0x1000).unwrap();
+
+                let source : String = format!("* = $1000\n {} {}\n", line.mnemonic, line.operand);
+                let output : Vec<u8> = match assemble_for(variant, &source)
+                {
+                    Ok(assembly) => assembly.image().1,
+                    Err(err) => panic!("{:?} {:02X} {}: {}", variant, opcode, source.trim(), err),
+                };
+                let again : Line = decode_for(variant, &output, 0x1000).unwrap();
+
+                assert_eq!((again.mnemonic, &again.operand), (line.mnemonic, &line.operand), "{:?} {:02X}", variant, opcode);
+                if inst.documented
+                {
+                    assert_eq!(output, line.bytes, "{:?} {:02X}", variant, opcode);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,495 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// The bus is everything the CPU can see on the other side of its address
+// and data lines. The core never touches memory directly, every fetch,
+// operand read, store and stack access goes through this trait.
+pub trait Bus
+{
+    fn read(&mut self, addr: u16) -> u8;
+    fn write(&mut self, addr: u16, data: u8);
+
+    // look at a byte without any of the side effects a real read could have,
+    // for debuggers and trace output. Plain memory can just read.
+    fn peek(&mut self, addr: u16) -> u8
+    {
+        return self.read(addr);
+    }
+
+    // interrupt request outputs of whatever sits on the bus, sampled by the
+    // CPU between instructions. IRQ is a level, NMI is acted on when it rises.
+    fn irq(&mut self) -> bool
+    {
+        return false;
+    }
+
+    fn nmi(&mut self) -> bool
+    {
+        return false;
+    }
+}
+
+// What the CPU did with the bus on one cycle. Every NMOS cycle is exactly
+// one read or one write, even the ones whose data is thrown away.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BusAccess
+{
+    Read,
+    Write,
+}
+
+// called with the address, the data and the direction of each bus cycle.
+pub type CycleHook = Box<dyn FnMut(u16, u8, BusAccess)>;
+
+// Flat 64 KiB of RAM covering the whole address space, the default bus.
+pub struct Ram
+{
+    pub mem: Box<[u8; 0x10000]>,
+}
+
+impl Ram
+{
+    pub fn new() -> Ram
+    {
+        return Ram { mem: Box::new([0x00; 0x10000]) };
+    }
+
+    // copy a program image into memory starting at addr, wrapping at $FFFF.
+    pub fn load(&mut self, addr: u16, data: &[u8])
+    {
+        for (i, byte) in data.iter().enumerate()
+        {
+            self.mem[addr.wrapping_add(i as u16) as usize] = *byte;
+        }
+    }
+}
+
+impl Default for Ram
+{
+    fn default() -> Ram
+    {
+        return Ram::new();
+    }
+}
+
+impl Bus for Ram
+{
+    fn read(&mut self, addr: u16) -> u8
+    {
+        return self.mem[addr as usize];
+    }
+
+    fn write(&mut self, addr: u16, data: u8)
+    {
+        self.mem[addr as usize] = data;
+    }
+}
+
+// A memory-mapped peripheral such as a timer, UART or display controller.
+// It sees offsets from the start of the window it is attached to, not
+// absolute addresses, so the same device works wherever a board puts it.
+pub trait Device
+{
+    fn read(&mut self, offset: u16) -> u8;
+    fn write(&mut self, offset: u16, data: u8);
+
+    // a device whose registers change when read, a status register that
+    // acknowledges an interrupt say, should override this to leave them be.
+    fn peek(&mut self, offset: u16) -> u8
+    {
+        return self.read(offset);
+    }
+
+    // a device holds irq high until the CPU has dealt with whatever raised it.
+    fn irq(&mut self) -> bool
+    {
+        return false;
+    }
+
+    fn nmi(&mut self) -> bool
+    {
+        return false;
+    }
+}
+
+// lets the host keep a handle on a device after attaching it to the bus.
+impl<T: Device> Device for Rc<RefCell<T>>
+{
+    fn read(&mut self, offset: u16) -> u8
+    {
+        return self.borrow_mut().read(offset);
+    }
+
+    fn write(&mut self, offset: u16, data: u8)
+    {
+        self.borrow_mut().write(offset, data);
+    }
+
+    fn peek(&mut self, offset: u16) -> u8
+    {
+        return self.borrow_mut().peek(offset);
+    }
+
+    fn irq(&mut self) -> bool
+    {
+        return self.borrow_mut().irq();
+    }
+
+    fn nmi(&mut self) -> bool
+    {
+        return self.borrow_mut().nmi();
+    }
+}
+
+// What a region of the memory map is backed by.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RegionKind
+{
+    Ram,              // read/write storage
+    Rom,              // read only, writes follow the map's RomWrite policy
+    Mirror(u16, u16), // (base, size) repeats size bytes starting at base
+    Device(usize),    // index into the map's attached devices
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Region
+{
+    pub start: u16,
+    pub end: u16, // inclusive
+    pub kind: RegionKind,
+}
+
+// What happens when the CPU stores into ROM.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RomWrite
+{
+    Ignore, // drop it silently, like the hardware does
+    Report, // drop it but keep the address and data in rom_writes
+}
+
+// A board memory map built out of address ranges. Ranges are checked newest
+// first so a later mapping can overlay an earlier one. Reads from addresses
+// nothing is mapped to return the last value seen on the data bus.
+pub struct MemoryMap
+{
+    pub mem: Box<[u8; 0x10000]>,
+    pub regions: Vec<Region>,
+    pub rom_write: RomWrite,
+    pub rom_writes: Vec<(u16, u8)>,
+    pub open_bus: u8,
+    pub devices: Vec<Box<dyn Device>>,
+}
+
+impl MemoryMap
+{
+    pub fn new() -> MemoryMap
+    {
+        return MemoryMap
+        {
+            mem: Box::new([0x00; 0x10000]),
+            regions: Vec::new(),
+            rom_write: RomWrite::Ignore,
+            rom_writes: Vec::new(),
+            open_bus: 0x00,
+            devices: Vec::new(),
+        };
+    }
+
+    pub fn map_ram(&mut self, start: u16, end: u16)
+    {
+        self.regions.push(Region { start, end, kind: RegionKind::Ram });
+    }
+
+    // map a ROM image at start, the region is as long as the image. Whatever
+    // runs past $FFFF is dropped rather than wrapping round onto $0000.
+    pub fn map_rom(&mut self, start: u16, data: &[u8])
+    {
+        if data.is_empty()
+        {
+            return;
+        }
+        let end : u16 = (start as usize + data.len() - 1).min(0xFFFF) as u16;
+        self.regions.push(Region { start, end, kind: RegionKind::Rom });
+        self.load(start, &data[..=(end - start) as usize]);
+    }
+
+    // make start..=end a repeat of the size bytes at base, e.g. the 2 KiB of
+    // RAM at $0000 showing up four times across $0000-$1FFF.
+    pub fn map_mirror(&mut self, start: u16, end: u16, base: u16, size: u16)
+    {
+        self.regions.push(Region { start, end, kind: RegionKind::Mirror(base, size) });
+    }
+
+    // route start..=end to a device instead of memory, returns the device id.
+    pub fn attach(&mut self, start: u16, end: u16, device: Box<dyn Device>) -> usize
+    {
+        let id : usize = self.devices.len();
+        self.devices.push(device);
+        self.regions.push(Region { start, end, kind: RegionKind::Device(id) });
+        return id;
+    }
+
+    // copy data straight into the backing store, ROM included.
+    pub fn load(&mut self, addr: u16, data: &[u8])
+    {
+        for (i, byte) in data.iter().enumerate()
+        {
+            self.mem[addr.wrapping_add(i as u16) as usize] = *byte;
+        }
+    }
+
+    // follow mirrors down to the address and kind that actually hold the byte,
+    // for a device the address returned is the offset into its window.
+    // A mirror only reflects what was mapped before it, so a mirror can sit
+    // on top of its own base, e.g. map_ram(0x0000, 0x07FF) followed by
+    // map_mirror(0x0000, 0x1FFF, 0x0000, 0x0800).
+    pub fn resolve(&self, addr: u16) -> Option<(u16, RegionKind)>
+    {
+        let mut addr : u16 = addr;
+        let mut limit : usize = self.regions.len();
+
+        loop
+        {
+            let (index, region) = self.regions[..limit].iter().enumerate().rev()
+                .find(|(_, r)| addr >= r.start && addr <= r.end)?;
+
+            match region.kind
+            {
+                RegionKind::Mirror(_, 0) => return None,
+                RegionKind::Mirror(base, size) =>
+                {
+                    addr = base.wrapping_add((addr - region.start) % size);
+                    limit = index;
+                }
+                RegionKind::Device(id) => return Some((addr - region.start, RegionKind::Device(id))),
+                kind => return Some((addr, kind)),
+            }
+        }
+    }
+}
+
+impl Default for MemoryMap
+{
+    fn default() -> MemoryMap
+    {
+        return MemoryMap::new();
+    }
+}
+
+impl Bus for MemoryMap
+{
+    fn read(&mut self, addr: u16) -> u8
+    {
+        match self.resolve(addr)
+        {
+            Some((offset, RegionKind::Device(id))) => self.open_bus = self.devices[id].read(offset),
+            Some((addr, _)) => self.open_bus = self.mem[addr as usize],
+            None => {}
+        }
+        return self.open_bus;
+    }
+
+    fn write(&mut self, addr: u16, data: u8)
+    {
+        self.open_bus = data;
+
+        match self.resolve(addr)
+        {
+            Some((addr, RegionKind::Ram)) => self.mem[addr as usize] = data,
+            Some((offset, RegionKind::Device(id))) => self.devices[id].write(offset, data),
+            Some((addr, RegionKind::Rom)) if self.rom_write == RomWrite::Report =>
+            {
+                self.rom_writes.push((addr, data));
+            }
+            _ => {}
+        }
+    }
+
+    // same as read but the data bus latch is left alone.
+    fn peek(&mut self, addr: u16) -> u8
+    {
+        return match self.resolve(addr)
+        {
+            Some((offset, RegionKind::Device(id))) => self.devices[id].peek(offset),
+            Some((addr, _)) => self.mem[addr as usize],
+            None => self.open_bus,
+        };
+    }
+
+    // the interrupt lines are wired-OR, any device can pull them.
+    fn irq(&mut self) -> bool
+    {
+        return self.devices.iter_mut().any(|d| d.irq());
+    }
+
+    fn nmi(&mut self) -> bool
+    {
+        return self.devices.iter_mut().any(|d| d.nmi());
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    // a register file that remembers the last write and drives the
+    // interrupt lines from flags the test sets.
+    #[derive(Default)]
+    struct Latch
+    {
+        regs: [u8; 8],
+        last_write: Option<(u16, u8)>,
+        irq: bool,
+        nmi: bool,
+    }
+
+    impl Device for Latch
+    {
+        fn read(&mut self, offset: u16) -> u8
+        {
+            return self.regs[offset as usize];
+        }
+
+        fn write(&mut self, offset: u16, data: u8)
+        {
+            self.regs[offset as usize] = data;
+            self.last_write = Some((offset, data));
+        }
+
+        fn irq(&mut self) -> bool
+        {
+            return self.irq;
+        }
+
+        fn nmi(&mut self) -> bool
+        {
+            return self.nmi;
+        }
+    }
+
+    // 2 KiB of RAM at $0000 seen four times across $0000-$1FFF, with the
+    // mirror laid over its own base as in the resolve() comment.
+    fn nes_ram() -> MemoryMap
+    {
+        let mut map : MemoryMap = MemoryMap::new();
+        map.map_ram(0x0000, 0x07FF);
+        map.map_mirror(0x0000, 0x1FFF, 0x0000, 0x0800);
+        return map;
+    }
+
+    #[test]
+    fn mirror_over_own_base()
+    {
+        let mut map : MemoryMap = nes_ram();
+
+        map.write(0x0801, 0x42);
+        assert_eq!(map.read(0x0001), 0x42);
+        assert_eq!(map.read(0x1001), 0x42);
+        assert_eq!(map.read(0x1801), 0x42);
+
+        assert_eq!(map.resolve(0x0000), Some((0x0000, RegionKind::Ram)));
+        assert_eq!(map.resolve(0x1FFF), Some((0x07FF, RegionKind::Ram)));
+        assert_eq!(map.resolve(0x2000), None);
+    }
+
+    #[test]
+    fn mirror_of_a_mirror()
+    {
+        let mut map : MemoryMap = MemoryMap::new();
+        map.map_ram(0x0000, 0x00FF);
+        map.map_mirror(0x0100, 0x01FF, 0x0000, 0x0100);
+        map.map_mirror(0x0200, 0x03FF, 0x0100, 0x0100);
+
+        assert_eq!(map.resolve(0x0305), Some((0x0005, RegionKind::Ram)));
+
+        map.write(0x0305, 0x99);
+        assert_eq!(map.read(0x0005), 0x99);
+        assert_eq!(map.read(0x0105), 0x99);
+    }
+
+    #[test]
+    fn empty_mirror_is_unmapped()
+    {
+        let mut map : MemoryMap = MemoryMap::new();
+        map.map_ram(0x0000, 0x00FF);
+        map.map_mirror(0x1000, 0x1FFF, 0x0000, 0x0000);
+
+        assert_eq!(map.resolve(0x1000), None);
+
+        // an unmapped read returns whatever was last on the data bus.
+        map.write(0x0010, 0x5A);
+        assert_eq!(map.read(0x1234), 0x5A);
+        assert_eq!(map.peek(0x1234), 0x5A);
+    }
+
+    #[test]
+    fn device_through_a_mirror()
+    {
+        let latch : Rc<RefCell<Latch>> = Rc::new(RefCell::new(Latch::default()));
+
+        let mut map : MemoryMap = MemoryMap::new();
+        let id : usize = map.attach(0x2000, 0x2007, Box::new(latch.clone()));
+        map.map_mirror(0x2008, 0x3FFF, 0x2000, 0x0008);
+
+        assert_eq!(map.resolve(0x2003), Some((0x0003, RegionKind::Device(id))));
+        assert_eq!(map.resolve(0x3456), Some((0x0006, RegionKind::Device(id))));
+
+        map.write(0x3456, 0x77);
+        assert_eq!(latch.borrow().last_write, Some((0x0006, 0x77)));
+        assert_eq!(map.read(0x2006), 0x77);
+        assert_eq!(map.read(0x200E), 0x77);
+    }
+
+    #[test]
+    fn rom_writes()
+    {
+        let mut map : MemoryMap = MemoryMap::new();
+        map.map_rom(0x8000, &[0x01, 0x02, 0x03]);
+
+        map.write(0x8001, 0x09);
+        assert_eq!(map.read(0x8001), 0x02);
+        assert!(map.rom_writes.is_empty());
+
+        map.rom_write = RomWrite::Report;
+        map.write(0x8002, 0x0A);
+        assert_eq!(map.read(0x8002), 0x03);
+        assert_eq!(map.rom_writes, vec![(0x8002, 0x0A)]);
+    }
+
+    #[test]
+    fn rom_past_the_top()
+    {
+        let mut map : MemoryMap = MemoryMap::new();
+        map.map_ram(0x0000, 0x00FF);
+        map.map_rom(0xFFFE, &[0x01, 0x02, 0x03, 0x04]);
+
+        assert_eq!(map.read(0xFFFE), 0x01);
+        assert_eq!(map.read(0xFFFF), 0x02);
+        assert_eq!(map.read(0x0000), 0x00);
+        assert_eq!(map.read(0x0001), 0x00);
+    }
+
+    #[test]
+    fn interrupt_lines_are_wired_or()
+    {
+        let first : Rc<RefCell<Latch>> = Rc::new(RefCell::new(Latch::default()));
+        let second : Rc<RefCell<Latch>> = Rc::new(RefCell::new(Latch::default()));
+
+        let mut map : MemoryMap = MemoryMap::new();
+        map.attach(0x4000, 0x4007, Box::new(first.clone()));
+        map.attach(0x4008, 0x400F, Box::new(second.clone()));
+
+        assert!(!map.irq());
+        assert!(!map.nmi());
+
+        second.borrow_mut().irq = true;
+        assert!(map.irq());
+        assert!(!map.nmi());
+
+        first.borrow_mut().nmi = true;
+        second.borrow_mut().irq = false;
+        assert!(!map.irq());
+        assert!(map.nmi());
+    }
+}
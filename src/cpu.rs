@@ -1,79 +1,323 @@
+use std::io::Write;
 
-use crate::cpuproc::instruction::INSTRUCTIONS;
+use crate::bus::BusAccess;
+use crate::instruction::{Instruction, INSTRUCTIONS, INSTRUCTIONS_65C02};
 use crate::cpuproc::match_addr;
 use crate::cpuproc::match_process;
 use crate::cpuproc::inst_cycles;
 use crate::cpuproc::CpuExecution;
+use crate::cpuproc::{interrupt, irq_asserted, sample_nmi};
+use crate::flags::StatusFlags;
+use crate::trace::trace_line;
+use crate::instruction::{AddrMode, InstructionType};
 
 
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SystemState
 {
     CpuInit,
     CpuInst,
+    CpuIrq,
+    CpuNmi,
     CpuSetFlag,
     None,
-    Jam,
+    Jam { pc: u16, opcode: u8 },  // locked up by a JAM until the next reset
+    Trap { pc: u16, opcode: u8 }, // a JAM handed back to the host, see jam_trap
+    Waiting, // 65C02 WAI, idle until IRQ or NMI
+    Stopped, // 65C02 STP, idle until the next reset
 }
 
-// so we need to process the instruction set
-// the instruction, addr mode, and cycles.
-pub fn process_instruction(opcode: u8, con: &mut CpuExecution) -> SystemState
+// which chip the core behaves as, picked when the CPU is built.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuVariant
 {
-    let opcode_converter = usize::from(opcode);
-    let opcode_instruction = &INSTRUCTIONS[opcode_converter];
-
-    let instruction = match_process(&opcode_instruction.inst_type, con);
-    let mode = match_addr(&opcode_instruction.mode, con);
-    let cycle = inst_cycles (opcode_instruction.cycles);
-    
-    print!("instruction: {:?} \n", opcode_instruction);
-     
-    print!("instruction: {:?} mode: {:?} cycle: {:?} \n", instruction, mode, cycle);
-    return SystemState::CpuInst;   
+    Nmos6502, // the original, undocumented opcodes and all
+    Wdc65C02, // the CMOS part: new opcodes, fixed bugs, no undocumented behaviour
+    Ricoh2A03, // the NES CPU, an NMOS core with decimal mode wired off
+    Mos6510,   // the C64 CPU, an NMOS core with an I/O port at $0000/$0001
 }
 
-pub fn cpu_init() -> SystemState
+impl CpuVariant
 {
-    return SystemState::CpuInit;
+    // the opcode table the variant decodes with.
+    pub const fn instructions(self) -> &'static [Instruction; 0x100]
+    {
+        return match self
+        {
+            CpuVariant::Nmos6502 | CpuVariant::Ricoh2A03 | CpuVariant::Mos6510 => &INSTRUCTIONS,
+            CpuVariant::Wdc65C02 => &INSTRUCTIONS_65C02,
+        };
+    }
 }
 
-pub fn cpu_read(addr: u16) -> u16
+// fetch the opcode at PC, work out the operand address, run the operation
+// and charge its cycles. The addressing mode always runs before the
+// operation so the operation sees the final address.
+pub(crate) fn process_instruction(con: &mut CpuExecution) -> SystemState
 {
-    if addr >= 0x0000 && addr <= 0xFFFF
+    // a jammed or stopped CPU does nothing at all, not even take interrupts.
+    if matches!(con.state, SystemState::Jam { .. } | SystemState::Stopped)
+    {
+        return con.state;
+    }
+
+    // WAI holds the CPU until an interrupt line goes active. An IRQ wakes
+    // it even with I set, it just carries on with the next instruction
+    // rather than taking the interrupt.
+    if con.state == SystemState::Waiting
+    {
+        if !sample_nmi(con) && !irq_asserted(con)
+        {
+            return con.state;
+        }
+        con.state = SystemState::CpuInst;
+    }
+
+    // interrupts are only taken between instructions.
+    if let Some(state) = poll_interrupts(con)
+    {
+        return state;
+    }
+
+    if con.trace.is_some()
+    {
+        let line : String = trace_line(con);
+
+        // a trace that can no longer be written is dropped, the CPU runs on.
+        if let Some(out) = con.trace.as_mut()
+        {
+            if writeln!(out, "{}", line).is_err()
+            {
+                con.trace = None;
+            }
+        }
+    }
+
+    let i_before : bool = con.rt_sr.contains(StatusFlags::I);
+
+    con.opcode = cpu_read_u8(con, con.rt_pc);
+    con.rt_pc = con.rt_pc.wrapping_add(1);
+
+    let opcode_instruction = &con.variant.instructions()[con.opcode as usize];
+
+    con.cycles = inst_cycles(opcode_instruction.cycles);
+
+    // JSR reads its operand around the stack pushes, it does its own addressing.
+    let mode = match opcode_instruction.inst_type
+    {
+        InstructionType::JSR => 0,
+        _ => match_addr(&opcode_instruction.mode, con),
+    };
+
+    indexed_dummy_read(con, opcode_instruction.mode, opcode_instruction.inst_type, mode == 1);
+
+    let instruction = match_process(&opcode_instruction.inst_type, con);
+
+    // the extra cycle is only paid when the mode crossed a page and the
+    // operation is one that has to wait for the corrected address.
+    con.cycles += mode & instruction;
+
+    // the interrupt logic samples I before CLI, SEI and PLP get to change
+    // it, so their effect on IRQ shows up one instruction late. RTI restores
+    // I in time for its own poll.
+    con.irq_inhibit = match opcode_instruction.inst_type
     {
-        return addr;
+        InstructionType::CLI | InstructionType::SEI | InstructionType::PLP => i_before,
+        _ => con.rt_sr.contains(StatusFlags::I),
+    };
+
+    // a taken branch that stays on its page skips the poll on its last
+    // cycle, so a pending interrupt waits for one more instruction.
+    if matches!(opcode_instruction.mode, AddrMode::REL) && con.cycles == opcode_instruction.cycles + 1
+    {
+        con.irq_delay = true;
     }
-    return 0x00;
+
+    // a trap is reported once, the CPU carries on from the next opcode.
+    if let SystemState::Trap { .. } = con.state
+    {
+        let state = con.state;
+        con.state = SystemState::CpuInst;
+        return state;
+    }
+
+    return con.state;
 }
 
-pub fn cpu_read_u8(addr: u8) -> u8
+// NMI wins over IRQ, and IRQ is ignored while I is set. Either one takes
+// the same 7 cycles as BRK.
+fn poll_interrupts(con: &mut CpuExecution) -> Option<SystemState>
 {
-    if addr >= 0x0000 && addr <= 0xFFFF
+    sample_nmi(con);
+
+    if con.irq_delay
+    {
+        con.irq_delay = false;
+        return None;
+    }
+
+    if con.nmi_pending
     {
-        return addr;
+        con.nmi_pending = false;
+        interrupt_fetch(con);
+        interrupt(con, 0xFFFA, false);
+        con.cycles = 7;
+        return Some(SystemState::CpuNmi);
     }
-    return 0x00;
+
+    if !con.irq_inhibit && irq_asserted(con)
+    {
+        interrupt_fetch(con);
+        interrupt(con, 0xFFFE, false);
+        con.cycles = 7;
+        return Some(SystemState::CpuIrq);
+    }
+
+    return None;
+}
+
+// an interrupt starts out as an ordinary opcode fetch that gets thrown
+// away, then reads the same byte again in place of an operand.
+fn interrupt_fetch(con: &mut CpuExecution)
+{
+    dummy_read(con, con.rt_pc);
+    dummy_read(con, con.rt_pc);
 }
 
-pub fn cpu_write(addr: u16, data: u8) -> u8
+// abs,X abs,Y and (zp),Y add the index to the low byte first and read from
+// there while the high byte is fixed up. A read that stayed on its page got
+// the right byte and is done, otherwise the read is wasted and the real
+// access follows. Stores and read-modify-writes always take the wasted read.
+// The 65C02 reads the last operand byte again instead of the half-finished
+// address, and its abs,X shifts skip the extra cycle when they can.
+fn indexed_dummy_read(con: &mut CpuExecution, mode: AddrMode, inst_type: InstructionType, crossed: bool)
 {
-    if addr >= 0x0000 && addr <= 0xFFFF
+    let index : u8 = match mode
     {
-        return data;
-    }
-    else  {
-        return 0;
+        AddrMode::AbsX => con.rt_x,
+        AddrMode::AbsY | AddrMode::IndY => con.rt_y,
+        _ => return,
+    };
+
+    let always : bool = match con.variant
+    {
+        CpuVariant::Wdc65C02 => writes_memory(inst_type) && !matches!(inst_type,
+            InstructionType::ASL | InstructionType::LSR | InstructionType::ROL | InstructionType::ROR),
+        _ => writes_memory(inst_type),
+    };
+
+    if crossed || always
+    {
+        let addr : u16 = match con.variant
+        {
+            CpuVariant::Wdc65C02 => con.rt_pc.wrapping_sub(1),
+            _ =>
+            {
+                let base : u16 = con.addr_abs.wrapping_sub(index as u16);
+                (base & 0xFF00) | (con.addr_abs & 0x00FF)
+            }
+        };
+        dummy_read(con, addr);
     }
+}
 
+// the instructions that write to their operand, stores and read-modify-writes.
+fn writes_memory(inst_type: InstructionType) -> bool
+{
+    return matches!(inst_type,
+        InstructionType::STA | InstructionType::STX | InstructionType::STY | InstructionType::SAX
+        | InstructionType::SHA | InstructionType::SHX | InstructionType::SHY | InstructionType::TAS
+        | InstructionType::ASL | InstructionType::LSR | InstructionType::ROL | InstructionType::ROR
+        | InstructionType::INC | InstructionType::DEC | InstructionType::SLO | InstructionType::RLA
+        | InstructionType::SRE | InstructionType::RRA | InstructionType::DCP | InstructionType::ISC
+        | InstructionType::STZ | InstructionType::TRB | InstructionType::TSB);
 }
 
+// the reset sequence: PC comes from the vector at $FFFC/$FFFD, the stack
+// pointer ends up at $FD, interrupts are masked and the internal latches
+// are cleared. It takes 7 cycles before the first opcode fetch. The 65C02
+// also comes out of reset in binary mode, and the 6510 with its port set
+// to input.
+pub(crate) fn cpu_init(con: &mut CpuExecution) -> SystemState
+{
+    // the port goes back to input first, a C64 banks its KERNAL back in
+    // before the vector is read.
+    if let Some(port) = con.port.as_mut()
+    {
+        port.reset(con.clock_count);
+    }
 
+    let lo : u16 = cpu_read(con, 0xFFFC);
+    let hi : u16 = cpu_read(con, 0xFFFD);
+    con.rt_pc = (hi << 8) | lo;
+
+    con.rt_sp = 0xFD;
+    con.rt_sr.insert(StatusFlags::I | StatusFlags::U);
+    if con.variant == CpuVariant::Wdc65C02
+    {
+        con.rt_sr.remove(StatusFlags::D);
+    }
+    con.irq_inhibit = true;
+    con.irq_delay = false;
+    con.nmi_pending = false;
+    con.state = SystemState::CpuInst;
 
+    con.fetch = 0x00;
+    con.addr_abs = 0x0000;
+    con.addr_rel = 0x0000;
 
+    con.cycles = 7;
 
+    return SystemState::CpuInit;
+}
 
+// all memory traffic from the core goes out over the bus the CPU owns.
+// Each call is one bus cycle, and cycle_hook hears about every one of them.
+// The 6510 answers reads of $0000 and $0001 from its port, the cycle
+// still happens on the bus, and passes writes to both.
+pub(crate) fn cpu_read(con: &mut CpuExecution, addr: u16) -> u16
+{
+    return cpu_read_u8(con, addr) as u16;
+}
 
+pub(crate) fn cpu_read_u8(con: &mut CpuExecution, addr: u16) -> u8
+{
+    let mut data : u8 = con.bus.read(addr);
+    if addr < 0x0002
+    {
+        if let Some(port) = con.port.as_mut()
+        {
+            data = port.read(addr, con.clock_count);
+        }
+    }
+    if let Some(hook) = con.cycle_hook.as_mut()
+    {
+        hook(addr, data, BusAccess::Read);
+    }
+    return data;
+}
 
+// a cycle where the CPU reads and ignores what comes back. Devices still
+// see it, a read can clear a status flag or pop a FIFO.
+pub(crate) fn dummy_read(con: &mut CpuExecution, addr: u16)
+{
+    cpu_read_u8(con, addr);
+}
 
+pub(crate) fn cpu_write(con: &mut CpuExecution, addr: u16, data: u8) -> u8
+{
+    con.bus.write(addr, data);
+    if addr < 0x0002
+    {
+        if let Some(port) = con.port.as_mut()
+        {
+            port.write(addr, data, con.clock_count);
+        }
+    }
+    if let Some(hook) = con.cycle_hook.as_mut()
+    {
+        hook(addr, data, BusAccess::Write);
+    }
+    return data;
+}
@@ -1,26 +1,11 @@
-#[path = "instruction.rs"] pub mod instruction;
-#[path = "cpu.rs"] pub mod cpu;
+use std::io::Write;
 
-use cpu::{cpu_read, cpu_read_u8, cpu_write};
-
-use crate::cpuproc::instruction as inst;
-use crate::cpu::SystemState;
-
-#[derive(Debug)]
-pub enum CondType
-{
-    // negative flag indicates negative value using bit position 7.
-    CtN    , // (1 << 7)  Negative Flag (N) 
-    CtV    , // (1 << 6)  Overflow Flag (V)
-    CtNone , // (1 << 5)  Ignore
-    CtB    , // (1 << 4)  Break
-    CtD    , // (1 << 3)  Decimal (use BCD for arithmetics)
-    CtI    , // (1 << 2)  Interrupt (IQR disable)
-    //zero flag is used to indicate all zero bits
-    CtZ    , // (1 << 1)  Zero
-    //used to carry over overflow in arithmetic operations.
-    CtC    , // (1 << 0)  Carry
-}
+use crate::bus::{Bus, CycleHook};
+use crate::flags::StatusFlags;
+use crate::cpu::{cpu_init, cpu_read, cpu_read_u8, cpu_write, dummy_read, process_instruction, CpuVariant, SystemState};
+use crate::instruction as inst;
+use crate::port::IoPort;
+use crate::trace;
 
 pub struct CpuExecution
 {
@@ -30,15 +15,292 @@ pub struct CpuExecution
     pub addr_rel: u16,
     pub opcode: u8,
     pub cycles: u8,
-    pub clock_count: u32,
+    pub clock_count: u64, // wide enough to never wrap
 
     pub rt_pc: u16, // Program Counter Registers
     pub rt_ac: u8, // Accumulator Register
     pub rt_x: u8, // Index Register X
     pub rt_y: u8, // Index Register Y
-    pub rt_sr: u8, // Status Register
+    pub rt_sr: StatusFlags, // Status Register
     pub rt_sp: u8, // Stack Pointer
-    pub rt_none: u8
+    pub rt_none: u8,
+
+    pub bus: Box<dyn Bus>, // everything outside the CPU
+
+    pub irq_line: bool,    // level triggered, held by the device until serviced
+    pub nmi_line: bool,    // edge triggered, only a rising edge counts
+    pub nmi_prev: bool,    // level of NMI the last time it was sampled
+    pub nmi_pending: bool, // an NMI edge was seen and not serviced yet
+    pub irq_inhibit: bool, // I as the interrupt logic saw it at the end of the last instruction
+    pub irq_delay: bool,   // hold interrupts off for one more instruction
+
+    pub variant: CpuVariant, // the chip being emulated, fixed at construction
+    pub decimal_mode: bool, // ADC and SBC honour D, off for the Ricoh 2A03
+    pub port: Option<IoPort>, // the 6510's I/O port, None on the other variants
+    pub magic: u8,          // ANE/LXA constant, differs between chip revisions
+    pub jam_trap: bool,     // hand JAMs back to the host instead of locking up
+
+    pub state: SystemState, // CpuInst while running, Jam, Waiting or Stopped when not
+
+    pub trace: Option<Box<dyn Write>>, // gets a nestest style line before every instruction
+    pub cycle_hook: Option<CycleHook>, // called on every bus cycle, in order
+}
+
+impl CpuExecution
+{
+    pub fn new(bus: Box<dyn Bus>) -> CpuExecution
+    {
+        return CpuExecution::with_variant(bus, CpuVariant::Nmos6502);
+    }
+
+    // a CPU that behaves as the given chip, new() builds an NMOS 6502.
+    pub fn with_variant(bus: Box<dyn Bus>, variant: CpuVariant) -> CpuExecution
+    {
+        return CpuExecution
+        {
+            fetch: 0x00,
+            temp: 0x0000,
+            addr_abs: 0x0000,
+            addr_rel: 0x00,
+            opcode: 0x00,
+            cycles: 0,
+            clock_count: 0,
+
+            rt_pc: 0x0000,
+            rt_ac: 0x00,
+            rt_x: 0x00,
+            rt_y: 0x00,
+            rt_sr: StatusFlags::U,
+            rt_sp: 0x00,
+            rt_none: 0x00,
+
+            bus,
+
+            irq_line: false,
+            nmi_line: false,
+            nmi_prev: false,
+            nmi_pending: false,
+            irq_inhibit: true,
+            irq_delay: false,
+
+            variant,
+            decimal_mode: variant != CpuVariant::Ricoh2A03,
+            port: if variant == CpuVariant::Mos6510 { Some(IoPort::new()) } else { None },
+            magic: 0xEE,
+            jam_trap: false,
+
+            state: SystemState::CpuInst,
+
+            trace: None,
+            cycle_hook: None,
+        };
+    }
+
+    // put the CPU through its reset sequence, see cpu_init.
+    pub fn reset(&mut self) -> SystemState
+    {
+        return cpu_init(self);
+    }
+
+    // drive the IRQ input, it stays asserted until the host clears it.
+    pub fn set_irq(&mut self, level: bool)
+    {
+        self.irq_line = level;
+    }
+
+    // drive the NMI input. The edge is latched straight away so a pulse the
+    // host raises and drops between two steps is not lost.
+    pub fn set_nmi(&mut self, level: bool)
+    {
+        self.nmi_line = level;
+        sample_nmi(self);
+    }
+
+    // run one whole instruction and return once it has finished, the
+    // cycles it took are added straight to clock_count. A waiting or
+    // stopped CPU returns straight away without using any.
+    pub fn step(&mut self) -> SystemState
+    {
+        // anything still in flight, like the reset sequence, finishes first.
+        // The clock count stops at u64::MAX, as it does in fast_forward.
+        self.clock_count = self.clock_count.saturating_add(self.cycles as u64);
+        self.cycles = 0;
+
+        let state = process_instruction(self);
+        self.clock_count = self.clock_count.saturating_add(self.cycles as u64);
+        self.cycles = 0;
+        return state;
+    }
+
+    // the trace line for the instruction at PC, see trace::trace_line.
+    pub fn trace_line(&mut self) -> String
+    {
+        return trace::trace_line(self);
+    }
+
+    // let cycles go by while the CPU is waiting or stopped, without ticking
+    // through them one at a time. Returns how many were skipped, which is
+    // none if the CPU is running. The clock count stops at u64::MAX rather
+    // than wrapping, so fewer may be skipped right at the end.
+    pub fn fast_forward(&mut self, cycles: u64) -> u64
+    {
+        if !matches!(self.state, SystemState::Waiting | SystemState::Stopped)
+        {
+            return 0;
+        }
+
+        self.clock_count = self.clock_count.saturating_add(self.cycles as u64);
+        self.cycles = 0;
+
+        let start : u64 = self.clock_count;
+        self.clock_count = self.clock_count.saturating_add(cycles);
+        return self.clock_count - start;
+    }
+
+    // one tick of the clock. Like the real chip a new instruction only
+    // starts once the previous one has used up all of its cycles.
+    pub fn clock(&mut self)
+    {
+        sample_nmi(self);
+
+        // a jammed CPU keeps being clocked, it just never gets anywhere. Its
+        // address lines are stuck high and it goes on reading $FFFF.
+        if self.cycles == 0
+        {
+            match self.state
+            {
+                SystemState::Jam { .. } => dummy_read(self, 0xFFFF),
+                _ => { process_instruction(self); }
+            }
+        }
+
+        self.cycles = self.cycles.saturating_sub(1);
+        self.clock_count = self.clock_count.saturating_add(1);
+    }
+}
+
+// read the byte at the program counter and step past it.
+fn read_pc(con: &mut CpuExecution) -> u16
+{
+    let pc = con.rt_pc;
+    con.rt_pc = con.rt_pc.wrapping_add(1);
+    return cpu_read(con, pc);
+}
+
+// the stack lives in page one and grows down, SP points at the next free slot.
+fn stack_push(con: &mut CpuExecution, data: u8)
+{
+    let addr : u16 = 0x0100 + con.rt_sp as u16;
+    cpu_write(con, addr, data);
+    con.rt_sp = con.rt_sp.wrapping_sub(1);
+}
+
+fn stack_pull(con: &mut CpuExecution) -> u8
+{
+    con.rt_sp = con.rt_sp.wrapping_add(1);
+    let addr : u16 = 0x0100 + con.rt_sp as u16;
+    return cpu_read_u8(con, addr);
+}
+
+// a pull spends a cycle reading the top of the stack while SP is
+// incremented, before the real pull.
+fn stack_wait(con: &mut CpuExecution)
+{
+    dummy_read(con, 0x0100 + con.rt_sp as u16);
+}
+
+// NMI is edge triggered, latch a low to high change on the line or from a
+// device on the bus until it gets serviced.
+pub(crate) fn sample_nmi(con: &mut CpuExecution) -> bool
+{
+    let level : bool = con.nmi_line || con.bus.nmi();
+
+    if level && !con.nmi_prev
+    {
+        con.nmi_pending = true;
+    }
+    con.nmi_prev = level;
+
+    return con.nmi_pending;
+}
+
+pub(crate) fn irq_asserted(con: &mut CpuExecution) -> bool
+{
+    return con.irq_line || con.bus.irq();
+}
+
+// push PC and status then jump through a vector, shared by BRK, IRQ and
+// NMI. Only BRK pushes status with B set, the unused bit is always set.
+pub(crate) fn interrupt(con: &mut CpuExecution, vector: u16, brk: bool)
+{
+    stack_push(con, ((con.rt_pc >> 8) & 0x00FF) as u8);
+    stack_push(con, (con.rt_pc & 0x00FF) as u8);
+
+    stack_push(con, con.rt_sr.pushed(brk));
+
+    con.rt_sr.insert(StatusFlags::I);
+    con.irq_inhibit = true;
+
+    // the 65C02 also drops back into binary mode for the handler.
+    if con.variant == CpuVariant::Wdc65C02
+    {
+        con.rt_sr.remove(StatusFlags::D);
+    }
+
+    // an NMI that turns up before the vector is fetched hijacks a BRK or
+    // IRQ, the sequence carries on but finishes at the NMI handler.
+    let mut vector : u16 = vector;
+    if vector == 0xFFFE && sample_nmi(con)
+    {
+        con.nmi_pending = false;
+        vector = 0xFFFA;
+    }
+
+    con.rt_pc = cpu_read(con, vector) | (cpu_read(con, vector.wrapping_add(1)) << 8);
+}
+
+// the table entry for the opcode being run.
+fn current(con: &CpuExecution) -> inst::Instruction
+{
+    return con.variant.instructions()[con.opcode as usize];
+}
+
+// accumulator and implied instructions work on A rather than memory.
+fn is_implied(con: &CpuExecution) -> bool
+{
+    return matches!(current(con).mode, inst::AddrMode::A | inst::AddrMode::IMP);
+}
+
+// load the operand for the current instruction into fetch, implied and
+// accumulator modes already have it from A.
+fn fetch(con: &mut CpuExecution) -> u8
+{
+    if !is_implied(con)
+    {
+        con.fetch = cpu_read_u8(con, con.addr_abs);
+    }
+    return con.fetch;
+}
+
+// read-modify-write instructions put their result back where the operand
+// came from. In memory the unmodified value is written first, the chip
+// stores it again while the ALU is still working out the new one. The
+// 65C02 reads the operand a second time instead.
+fn write_back(con: &mut CpuExecution, data: u8)
+{
+    if is_implied(con)
+    {
+        con.rt_ac = data;
+    }
+    else
+    {
+        match con.variant
+        {
+            CpuVariant::Wdc65C02 => dummy_read(con, con.addr_abs),
+            _ => { cpu_write(con, con.addr_abs, con.fetch); }
+        }
+        cpu_write(con, con.addr_abs, data);
+    }
 }
 
 // Addr mode functions.
@@ -50,11 +312,8 @@ pub struct CpuExecution
 
 fn absolute_addr(con: &mut CpuExecution) -> u8
 {
-    let lo : u16 = cpu::cpu_read(con.rt_pc as u16);
-    con.rt_pc = con.rt_pc + 1;
-
-    let hi : u16 = cpu::cpu_read(con.rt_pc as u16);
-    con.rt_pc = con.rt_pc + 1;
+    let lo : u16 = read_pc(con);
+    let hi : u16 = read_pc(con);
 
     con.addr_abs = (hi << 8) | lo;
 
@@ -63,13 +322,11 @@ fn absolute_addr(con: &mut CpuExecution) -> u8
 
 fn absolute_x_addr(con: &mut CpuExecution) -> u8
 {
-    let lo : u16 = cpu::cpu_read(con.rt_pc as u16);
-    con.rt_pc = con.rt_pc + 1;
-    let hi : u16 = cpu::cpu_read(con.rt_pc as u16);
-    con.rt_pc = con.rt_pc + 1;
+    let lo : u16 = read_pc(con);
+    let hi : u16 = read_pc(con);
 
     con.addr_abs = (hi << 8) | lo;
-    con.addr_abs += con.rt_x as u16;
+    con.addr_abs = con.addr_abs.wrapping_add(con.rt_x as u16);
 
     if (con.addr_abs & 0xFF00) != (hi << 8)
     {
@@ -84,13 +341,11 @@ fn absolute_x_addr(con: &mut CpuExecution) -> u8
 
 fn absolute_y_addr(con: &mut CpuExecution) -> u8
 {
-    let lo : u16 = cpu::cpu_read(con.rt_pc as u16);
-    con.rt_pc = con.rt_pc + 1;
-    let hi : u16 = cpu::cpu_read(con.rt_pc as u16);
-    con.rt_pc = con.rt_pc + 1;
+    let lo : u16 = read_pc(con);
+    let hi : u16 = read_pc(con);
 
     con.addr_abs = (hi << 8) | lo;
-    con.addr_abs += con.rt_y as u16;
+    con.addr_abs = con.addr_abs.wrapping_add(con.rt_y as u16);
 
     if (con.addr_abs & 0xFF00) != (hi << 8)
     {
@@ -104,48 +359,79 @@ fn absolute_y_addr(con: &mut CpuExecution) -> u8
 
 fn immediate_addr(con: &mut CpuExecution) -> u8
 {
-    println!("this is immediate");
-    con.addr_abs = (con.rt_pc + 1) as u16;
+    con.addr_abs = con.rt_pc;
+    con.rt_pc = con.rt_pc.wrapping_add(1);
     return 0;
 }
 
+// one-byte instructions still read the byte after the opcode, and ignore it.
+// The single cycle NOPs of the 65C02 are done before they get that far.
 fn implied_addr(con: &mut CpuExecution) -> u8
 {
+    if current(con).cycles > 1
+    {
+        dummy_read(con, con.rt_pc);
+    }
     con.fetch = con.rt_ac;
     return 0;
 }
 
 fn indirect_addr(con: &mut CpuExecution) -> u8
 {
-    let lo : u16 = cpu::cpu_read(con.rt_pc as u16);
-    con.rt_pc = con.rt_pc + 1;
-
-    let hi : u16 = cpu::cpu_read(con.rt_pc as u16);
-    con.rt_pc = con.rt_pc + 1;
+    let lo : u16 = read_pc(con);
+    let hi : u16 = read_pc(con);
 
     let ptr : u16 = (hi << 8) | lo;
 
-    if lo == 0x00FF
+    // the pointer high byte never carries into the next page, JMP ($xxFF)
+    // fetches its high byte from $xx00. The 65C02 spends a cycle getting
+    // the carry right.
+    let ptr_hi : u16 = match con.variant
     {
-        con.addr_abs = (cpu::cpu_read(ptr & 0xFF00) << 8) | cpu::cpu_read(ptr + 0);
+        CpuVariant::Wdc65C02 =>
+        {
+            dummy_read(con, con.rt_pc.wrapping_sub(1));
+            ptr.wrapping_add(1)
+        }
+        _ => (ptr & 0xFF00) | (ptr.wrapping_add(1) & 0x00FF),
+    };
 
-    }
-    else 
-    {
-        con.addr_abs = (cpu::cpu_read(ptr + 1) << 8) | cpu::cpu_read(ptr + 0);
-    }
+    let target_lo : u16 = cpu_read(con, ptr);
+    let target_hi : u16 = cpu_read(con, ptr_hi);
+
+    con.addr_abs = (target_hi << 8) | target_lo;
 
     return 0;
 
 }
 
+// JMP ($nnnn,X) on the 65C02, X is added to the pointer while the last
+// operand byte is read again.
+fn absolute_indexed_indirect_addr(con: &mut CpuExecution) -> u8
+{
+    let lo : u16 = read_pc(con);
+    let hi : u16 = read_pc(con);
+    dummy_read(con, con.rt_pc.wrapping_sub(1));
+
+    let ptr : u16 = ((hi << 8) | lo).wrapping_add(con.rt_x as u16);
+
+    let target_lo : u16 = cpu_read(con, ptr);
+    let target_hi : u16 = cpu_read(con, ptr.wrapping_add(1));
+
+    con.addr_abs = (target_hi << 8) | target_lo;
+
+    return 0;
+}
+
 fn indirect_x_addr(con: &mut CpuExecution) -> u8
 {
-    let t : u16 = cpu::cpu_read(con.rt_pc);
-    con.rt_pc = con.rt_pc + 1;
+    let t : u16 = read_pc(con);
+
+    // the pointer is read once before X is added to it.
+    dummy_read(con, t);
 
-    let lo : u16 = cpu::cpu_read((t+(con.rt_x as u16))&0x00FF);
-    let hi : u16 = cpu::cpu_read((t+(con.rt_x as u16 )+1)&0x00FF);
+    let lo : u16 = cpu_read(con, (t + (con.rt_x as u16)) & 0x00FF);
+    let hi : u16 = cpu_read(con, (t + (con.rt_x as u16) + 1) & 0x00FF);
 
     con.addr_abs = (hi << 8) | lo;
 
@@ -154,14 +440,13 @@ fn indirect_x_addr(con: &mut CpuExecution) -> u8
 
 fn indirect_y_addr(con: &mut CpuExecution) -> u8
 {
-    let t : u16 = cpu::cpu_read(con.rt_pc);
-    con.rt_pc = con.rt_pc + 1;
+    let t : u16 = read_pc(con);
 
-    let lo : u16 = cpu::cpu_read(t &0x00FF);
-    let hi : u16 = cpu::cpu_read((t+1)&0x00FF);
+    let lo : u16 = cpu_read(con, t & 0x00FF);
+    let hi : u16 = cpu_read(con, (t + 1) & 0x00FF);
 
     con.addr_abs = (hi << 8) | lo;
-    con.addr_abs = con.addr_abs + (con.rt_y as u16);
+    con.addr_abs = con.addr_abs.wrapping_add(con.rt_y as u16);
 
     if (con.addr_abs & 0xFF00) != (hi << 8)
     {
@@ -173,17 +458,38 @@ fn indirect_y_addr(con: &mut CpuExecution) -> u8
     }
 }
 
+// ($zp) on the 65C02, (zp),Y without the Y.
+fn zero_page_indirect_addr(con: &mut CpuExecution) -> u8
+{
+    let t : u16 = read_pc(con);
+
+    let lo : u16 = cpu_read(con, t & 0x00FF);
+    let hi : u16 = cpu_read(con, (t + 1) & 0x00FF);
+
+    con.addr_abs = (hi << 8) | lo;
+
+    return 0;
+}
+
+// BBR and BBS on the 65C02 carry both a zero page address and a branch
+// offset.
+fn zero_page_relative_addr(con: &mut CpuExecution) -> u8
+{
+    con.addr_abs = read_pc(con) & 0x00FF;
+    relative_addr(con);
+    return 0;
+}
+
 fn jam_addr() -> u8
 {
-    return 1;
+    return 0;
 }
 
 fn relative_addr(con: &mut CpuExecution) -> u8
 {
-    con.addr_rel = cpu::cpu_read(con.rt_pc);
-    con.rt_pc = con.rt_pc + 1;
+    con.addr_rel = read_pc(con);
 
-    if (con.addr_rel & 0x80) == 1
+    if (con.addr_rel & 0x80) != 0
     {
         con.addr_rel |= 0xFF00;
     }
@@ -192,797 +498,1366 @@ fn relative_addr(con: &mut CpuExecution) -> u8
 
 fn zero_page_addr(con: &mut CpuExecution) -> u8
 {
-    con.addr_abs = cpu::cpu_read(con.rt_pc);
-    con.rt_pc =  con.rt_pc + 1;
+    con.addr_abs = read_pc(con);
     con.addr_abs &= 0x00FF;
     return 0;
 }
 
+// zero page is read at the base address while the index is being added.
 fn zero_page_x_addr(con: &mut CpuExecution) -> u8
 {
-    con.addr_abs = cpu::cpu_read(con.rt_pc as u16 + con.rt_x as u16);
-    con.rt_pc =  con.rt_pc + 1;
+    let base : u16 = read_pc(con);
+    dummy_read(con, base);
+
+    con.addr_abs = base + con.rt_x as u16;
     con.addr_abs &= 0x00FF;
     return 0;
 }
 
 fn zero_page_y_addr(con: &mut CpuExecution) -> u8
 {
-    con.addr_abs = cpu::cpu_read(con.rt_pc as u16 + con.rt_y as u16);
-    con.rt_pc =  con.rt_pc + 1;
+    let base : u16 = read_pc(con);
+    dummy_read(con, base);
+
+    con.addr_abs = base + con.rt_y as u16;
     con.addr_abs &= 0x00FF;
     return 0;
 
 }
 
-// Instruction Type
-fn adc(con: &mut CpuExecution) -> u8
+// the adder behind ADC, and the undocumented opcodes built on it.
+fn add_with_carry(con: &mut CpuExecution, value: u8)
 {
-    let temp = con.rt_ac + con.fetch + get_flag(CondType::CtC, con);
-
-    let zval : bool = (temp & 0x00FF) == 1; 
-    set_flag(CondType::CtZ,  zval, con);
-
-    let nval : bool = (temp & 0x80) == 1;
-    set_flag(CondType::CtN, nval, con);
-        
-    let cval : bool = temp > 255;
-    set_flag(CondType::CtC, cval, con);
+    if con.decimal_mode && con.rt_sr.contains(StatusFlags::D)
+    {
+        add_decimal(con, value);
+        if con.variant == CpuVariant::Wdc65C02
+        {
+            decimal_fixup(con);
+        }
+        return;
+    }
 
-    let vval : bool = !((con.rt_ac ^ con.fetch) & (con.rt_ac ^ temp)) == 1;
-    set_flag(CondType::CtV, vval, con);
+    let temp : u16 = con.rt_ac as u16 + value as u16 + con.rt_sr.carry() as u16;
 
-    con.rt_ac = temp & 0x00FF;
-    
-    return 1;
-}
+    let cval : bool = temp > 0x00FF;
+    con.rt_sr.set(StatusFlags::C, cval);
 
-fn and(con: &mut CpuExecution) -> u8
-{
-    con.rt_ac = con.rt_ac & con.fetch;
+    let zval : bool = (temp & 0x00FF) == 0x0000;
+    con.rt_sr.set(StatusFlags::Z, zval);
 
-    let zval : bool = con.rt_ac == 0x00;
-    set_flag(CondType::CtC, zval, con);
+    let nval : bool = (temp & 0x0080) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
 
-    let nval : bool = (con.rt_ac & 0x80) == 1;
-    set_flag(CondType::CtN, nval, con);
+    // overflow when both inputs share a sign and the result does not.
+    let vval : bool = (!(con.rt_ac as u16 ^ value as u16) & (con.rt_ac as u16 ^ temp) & 0x0080) != 0;
+    con.rt_sr.set(StatusFlags::V, vval);
 
-    return 1;
+    con.rt_ac = (temp & 0x00FF) as u8;
 }
 
-fn asl(con: &mut CpuExecution) -> u8
+// NMOS decimal add. Each nibble is corrected by 6 when it passes 9. Z comes
+// from the plain binary sum, while N and V are taken from the high nibble
+// after the low nibble has been corrected but before the high one is, which
+// is why they look meaningless in BCD but are still exactly reproducible.
+fn add_decimal(con: &mut CpuExecution, value: u8)
 {
-    let temp =  get_flag(CondType::CtC, con);
-
-    let zval : bool = con.rt_ac == 0x00FF;
-    set_flag(CondType::CtC, zval, con);
+    let a : u16 = con.rt_ac as u16;
+    let b : u16 = value as u16;
+    let carry : u16 = con.rt_sr.carry() as u16;
 
-    let nval : bool = (con.rt_ac  & 0x80) == 1;
-    set_flag(CondType::CtN, nval, con);
+    let zval : bool = ((a + b + carry) & 0x00FF) == 0x0000;
+    con.rt_sr.set(StatusFlags::Z, zval);
 
-    //set_flag(CondType::CtC, (temp & 0xFF00) > 0, con);
+    let mut lo : u16 = (a & 0x0F) + (b & 0x0F) + carry;
+    if lo > 0x09
+    {
+        lo += 0x06;
+    }
 
-    // add more logic
+    let mut hi : u16 = (a >> 4) + (b >> 4) + if lo > 0x0F {1} else {0};
 
-    return 1;
+    let nval : bool = (hi & 0x08) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
 
-}
+    let vval : bool = (!(a ^ b) & (a ^ (hi << 4)) & 0x0080) != 0;
+    con.rt_sr.set(StatusFlags::V, vval);
 
-fn bcc(con: &mut CpuExecution) -> u8
-{
-    if get_flag(CondType::CtC, con) == 0
+    if hi > 0x09
     {
-        con.cycles = con.cycles +1;
-        con.addr_abs = con.rt_pc + con.addr_rel;
-
-        if(con.addr_abs & 0xFF00) != (con.rt_pc & 0xFF00)
-        {
-            con.cycles = con.cycles +1;
-        }
-
-        con.rt_pc = con.addr_abs;
+        hi += 0x06;
     }
 
-    return 0;
+    let cval : bool = hi > 0x0F;
+    con.rt_sr.set(StatusFlags::C, cval);
+
+    con.rt_ac = (((hi << 4) | (lo & 0x0F)) & 0x00FF) as u8;
 }
 
-fn bcs(con: &mut CpuExecution) -> u8
+// the subtractor behind SBC, and the undocumented opcodes built on it.
+fn subtract_with_carry(con: &mut CpuExecution, value: u8)
 {
-    if get_flag(CondType::CtC, con) == 1
+    // subtraction is addition of the ones' complement, borrow is !C.
+    let inverted : u16 = (value as u16) ^ 0x00FF;
+    let temp : u16 = con.rt_ac as u16 + inverted + con.rt_sr.carry() as u16;
+
+    // NMOS sets every flag from the binary result, even in decimal mode.
+    let decimal : bool = con.decimal_mode && con.rt_sr.contains(StatusFlags::D);
+    let result : u8 = match con.variant
     {
-        con.cycles = con.cycles + 1;
-        con.addr_abs = con.rt_pc + con.addr_rel;
+        _ if !decimal => (temp & 0x00FF) as u8,
+        CpuVariant::Wdc65C02 => subtract_decimal_cmos(con, value),
+        _ => subtract_decimal(con, value),
+    };
 
-        if(con.addr_abs & 0xFF00) != (con.rt_pc & 0xFF00)
-        {
-            con.cycles = con.cycles +1;
-        }
+    let cval : bool = (temp & 0xFF00) != 0;
+    con.rt_sr.set(StatusFlags::C, cval);
 
-        con.rt_pc = con.addr_abs;
+    let zval : bool = (temp & 0x00FF) == 0x0000;
+    con.rt_sr.set(StatusFlags::Z, zval);
 
-    }
-    return 0;
-}
+    let nval : bool = (temp & 0x0080) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
 
-fn beq(con: &mut CpuExecution) -> u8
-{
-    if get_flag(CondType::CtZ, con) == 1
-    {
-        con.cycles= con.cycles + 1;
-        con.addr_abs =  con.rt_pc + con.addr_rel;
+    let vval : bool = ((temp ^ con.rt_ac as u16) & (temp ^ inverted) & 0x0080) != 0;
+    con.rt_sr.set(StatusFlags::V, vval);
 
-        if(con.addr_abs & 0xFF00) != (con.rt_pc & 0xFF00)
-        {
-            con.cycles = con.cycles + 1;
-        }
+    con.rt_ac = result;
 
-        con.rt_pc = con.addr_abs;
+    if decimal && con.variant == CpuVariant::Wdc65C02
+    {
+        decimal_fixup(con);
     }
-    return 0;
-
 }
 
-fn bit(con: &mut CpuExecution) -> u8
+// NMOS decimal subtract, each nibble that borrows is corrected by 6.
+fn subtract_decimal(con: &mut CpuExecution, value: u8) -> u8
 {
-    let temp : u8 = con.rt_ac & con.fetch;
+    let a : i16 = con.rt_ac as i16;
+    let b : i16 = value as i16;
+    let borrow : i16 = 1 - con.rt_sr.carry() as i16;
 
-    let zval : bool = temp & 0x00FF == 0x00;
-    set_flag(CondType::CtZ, zval, con);
+    let mut lo : i16 = (a & 0x0F) - (b & 0x0F) - borrow;
+    let mut hi : i16 = (a >> 4) - (b >> 4);
 
-    let vval : bool = con.fetch & (1 << 6) == 1;
-    set_flag(CondType::CtV, vval, con);
+    if lo < 0
+    {
+        lo -= 0x06;
+        hi -= 1;
+    }
 
-    let nval : bool = con.fetch & (1 << 7) == 1;
-    set_flag(CondType::CtN, nval, con);
+    if hi < 0
+    {
+        hi -= 0x06;
+    }
 
-    return 0;
+    return (((hi << 4) | (lo & 0x0F)) & 0x00FF) as u8;
 }
 
-fn brk(con: &mut CpuExecution) -> u8
+// 65C02 decimal subtract. The borrow out of the low nibble is taken off the
+// whole byte rather than the high nibble, which only makes a difference
+// for digits that are not valid BCD.
+fn subtract_decimal_cmos(con: &mut CpuExecution, value: u8) -> u8
 {
-    con.rt_pc = con.rt_pc + 1;
+    let a : i16 = con.rt_ac as i16;
+    let b : i16 = value as i16;
+    let borrow : i16 = 1 - con.rt_sr.carry() as i16;
 
-    
-    set_flag(CondType::CtI, true, con);
-    cpu_write(0x0100 + con.rt_sp as u16, ((con.rt_pc >> 8) & 0x00FF) as u8);
-    con.rt_sp = con.rt_sp - 1;
-    cpu_write(0x0100 + con.rt_sp as u16, (con.rt_pc & 0x00FF) as u8);
-    con.rt_sp = con.rt_sp - 1;
+    let lo : i16 = (a & 0x0F) - (b & 0x0F) - borrow;
+    let mut result : i16 = a - b - borrow;
 
-    set_flag(CondType::CtB,true, con);
-    cpu_write(0x100 + con.rt_sp as u16, con.rt_sr);
-    con.rt_sp = con.rt_sp - 1;
-    set_flag(CondType::CtB, false, con);
+    if result < 0
+    {
+        result -= 0x60;
+    }
 
-    con.rt_pc = cpu_read(0xFFFE) | (cpu_read(0xFFFF) << 8);
+    if lo < 0
+    {
+        result -= 0x06;
+    }
 
-    return 0;
+    return (result & 0x00FF) as u8;
 }
 
-fn bpl(con: &mut CpuExecution) -> u8
+// the 65C02 spends one more cycle on a decimal ADC or SBC, reading the
+// operand again, and in return N and Z describe the BCD result.
+fn decimal_fixup(con: &mut CpuExecution)
 {
-    if get_flag(CondType::CtN, con) == 0
-    {
-        con.cycles = con.cycles + 1;
-        con.addr_abs =  con.rt_pc + con.addr_rel;
-
-        if(con.addr_abs & 0xFF00) != (con.rt_pc & 0xFF00)
-        {
-            con.cycles = con.cycles +1;
-        }
-
-        con.rt_pc = con.addr_abs;
+    con.cycles += 1;
+    dummy_read(con, con.addr_abs);
 
-    }
-    return 0;
+    let zval : bool = con.rt_ac == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
 
+    let nval : bool = (con.rt_ac & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
 }
 
-fn bmi(con: &mut CpuExecution) -> u8
+// the 65C02 takes its abs,X shifts in 6 cycles unless the page changes,
+// so for it they pay the page penalty like a read does.
+fn shift_penalty(con: &CpuExecution) -> u8
 {
-    if get_flag(CondType::CtN, con) == 1
+    return match con.variant
     {
-        con.cycles= con.cycles + 1;
-        con.addr_abs =  con.rt_pc + con.addr_rel;
-
-        if(con.addr_abs & 0xFF00) != (con.rt_pc & 0xFF00)
-        {
-            con.cycles = con.cycles + 1;
-        }
+        CpuVariant::Wdc65C02 => 1,
+        _ => 0,
+    };
+}
 
-        con.rt_pc = con.addr_abs;
-    }
-    return 0;
+// Instruction Type
+fn adc(con: &mut CpuExecution) -> u8
+{
+    fetch(con);
+    add_with_carry(con, con.fetch);
+    return 1;
 }
 
-fn bne(con: &mut CpuExecution) -> u8
+fn and(con: &mut CpuExecution) -> u8
 {
-    if get_flag(CondType::CtZ, con) == 0
-    {
-        con.cycles= con.cycles + 1;
-        con.addr_abs =  con.rt_pc + con.addr_rel;
+    fetch(con);
+    con.rt_ac &= con.fetch;
 
-        if(con.addr_abs & 0xFF00) != (con.rt_pc & 0xFF00)
-        {
-            con.cycles = con.cycles + 1;
-        }
+    let zval : bool = con.rt_ac == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
 
-        con.rt_pc = con.addr_abs;
-    }
-    return 0;
+    let nval : bool = (con.rt_ac & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
 
+    return 1;
 }
 
-fn bvc(con: &mut CpuExecution) -> u8
+fn asl(con: &mut CpuExecution) -> u8
 {
-    if get_flag(CondType::CtV, con) == 0
-    {
-        con.cycles= con.cycles + 1;
-        con.addr_abs =  con.rt_pc + con.addr_rel;
+    fetch(con);
+    let temp : u16 = (con.fetch as u16) << 1;
 
-        if(con.addr_abs & 0xFF00) != (con.rt_pc & 0xFF00)
-        {
-            con.cycles = con.cycles + 1;
-        }
+    let cval : bool = (temp & 0xFF00) > 0;
+    con.rt_sr.set(StatusFlags::C, cval);
 
-        con.rt_pc = con.addr_abs;
-    }
-    return 0;
+    let zval : bool = (temp & 0x00FF) == 0x0000;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    let nval : bool = (temp & 0x0080) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
+
+    write_back(con, temp as u8);
+
+    return shift_penalty(con);
 
 }
 
-fn bvs(con: &mut CpuExecution) -> u8
+// every branch ends up here. Taking it costs one more cycle, and one more
+// again when the target is on a different page to the next instruction.
+// Both extra cycles read from PC, the second one before its high byte has
+// been fixed up.
+fn branch(con: &mut CpuExecution, taken: bool) -> u8
 {
-    if get_flag(CondType::CtV, con) == 1
+    if taken
     {
-        con.cycles= con.cycles + 1;
-        con.addr_abs =  con.rt_pc + con.addr_rel;
+        con.cycles += 1;
+        dummy_read(con, con.rt_pc);
+        con.addr_abs = con.rt_pc.wrapping_add(con.addr_rel);
 
-        if(con.addr_abs & 0xFF00) != (con.rt_pc & 0xFF00)
+        if (con.addr_abs & 0xFF00) != (con.rt_pc & 0xFF00)
         {
-            con.cycles = con.cycles + 1;
+            con.cycles += 1;
+            dummy_read(con, (con.rt_pc & 0xFF00) | (con.addr_abs & 0x00FF));
         }
 
         con.rt_pc = con.addr_abs;
     }
-    return 1;
-}
 
-fn clc(con: &mut CpuExecution) -> u8
-{
-    set_flag(CondType::CtC, false, con);
     return 0;
 }
 
-fn cld(con: &mut CpuExecution) -> u8
+// the Rockwell bit instructions pick their bit with bits 4-6 of the opcode.
+fn opcode_bit(con: &CpuExecution) -> u8
 {
-    set_flag(CondType::CtD, false, con);
-    return 0;
+    return 1 << ((con.opcode >> 4) & 0x07);
 }
 
-fn cli(con: &mut CpuExecution) -> u8
+// BBR and BBS read the zero page byte, and read it once more while the
+// bit is tested, before they branch.
+fn bbr(con: &mut CpuExecution) -> u8
 {
-    set_flag(CondType::CtI, false, con);
-    return 0;
+    fetch(con);
+    dummy_read(con, con.addr_abs);
+
+    let taken : bool = (con.fetch & opcode_bit(con)) == 0;
+    return branch(con, taken);
 }
 
-fn clv(con: &mut CpuExecution) -> u8
+fn bbs(con: &mut CpuExecution) -> u8
 {
-    set_flag(CondType::CtV, false, con);
+    fetch(con);
+    dummy_read(con, con.addr_abs);
+
+    let taken : bool = (con.fetch & opcode_bit(con)) != 0;
+    return branch(con, taken);
+}
+
+fn bcc(con: &mut CpuExecution) -> u8
+{
+    let taken : bool = !con.rt_sr.contains(StatusFlags::C);
+    return branch(con, taken);
+}
+
+fn bcs(con: &mut CpuExecution) -> u8
+{
+    let taken : bool = con.rt_sr.contains(StatusFlags::C);
+    return branch(con, taken);
+}
+
+fn beq(con: &mut CpuExecution) -> u8
+{
+    let taken : bool = con.rt_sr.contains(StatusFlags::Z);
+    return branch(con, taken);
+}
+
+// BIT #imm on the 65C02 only sets Z, N and V would just copy the operand.
+fn bit(con: &mut CpuExecution) -> u8
+{
+    fetch(con);
+    let temp : u8 = con.rt_ac & con.fetch;
+
+    let zval : bool = temp == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    if current(con).mode == inst::AddrMode::IMM
+    {
+        return 0;
+    }
+
+    let vval : bool = (con.fetch & (1 << 6)) != 0;
+    con.rt_sr.set(StatusFlags::V, vval);
+
+    let nval : bool = (con.fetch & (1 << 7)) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
+
+    return 1;
+}
+
+fn bra(con: &mut CpuExecution) -> u8
+{
+    return branch(con, true);
+}
+
+fn brk(con: &mut CpuExecution) -> u8
+{
+    // BRK skips the padding byte after the opcode.
+    con.rt_pc = con.rt_pc.wrapping_add(1);
+
+    interrupt(con, 0xFFFE, true);
+
+    return 0;
+}
+
+fn bpl(con: &mut CpuExecution) -> u8
+{
+    let taken : bool = !con.rt_sr.contains(StatusFlags::N);
+    return branch(con, taken);
+}
+
+fn bmi(con: &mut CpuExecution) -> u8
+{
+    let taken : bool = con.rt_sr.contains(StatusFlags::N);
+    return branch(con, taken);
+}
+
+fn bne(con: &mut CpuExecution) -> u8
+{
+    let taken : bool = !con.rt_sr.contains(StatusFlags::Z);
+    return branch(con, taken);
+}
+
+fn bvc(con: &mut CpuExecution) -> u8
+{
+    let taken : bool = !con.rt_sr.contains(StatusFlags::V);
+    return branch(con, taken);
+}
+
+fn bvs(con: &mut CpuExecution) -> u8
+{
+    let taken : bool = con.rt_sr.contains(StatusFlags::V);
+    return branch(con, taken);
+}
+
+fn clc(con: &mut CpuExecution) -> u8
+{
+    con.rt_sr.set(StatusFlags::C, false);
+    return 0;
+}
+
+fn cld(con: &mut CpuExecution) -> u8
+{
+    con.rt_sr.set(StatusFlags::D, false);
+    return 0;
+}
+
+fn cli(con: &mut CpuExecution) -> u8
+{
+    con.rt_sr.set(StatusFlags::I, false);
+    return 0;
+}
+
+fn clv(con: &mut CpuExecution) -> u8
+{
+    con.rt_sr.set(StatusFlags::V, false);
     return 0;
 }
 
 fn cmp(con: &mut CpuExecution) -> u8
 {
+    fetch(con);
+    let temp : u8 = con.rt_ac.wrapping_sub(con.fetch);
 
-    let temp : u16 = con.rt_ac as u16 - con.fetch as u16;
-    
     let cval : bool = con.rt_ac >= con.fetch;
-    set_flag(CondType::CtZ, cval, con);
+    con.rt_sr.set(StatusFlags::C, cval);
 
-    let zval : bool = temp & 0x00FF == 0x0000;
-    set_flag(CondType::CtZ, zval, con);
+    let zval : bool = temp == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
 
-    let nval : bool = (temp & 0x0080) == 1;
-    set_flag(CondType::CtN, nval, con);
+    let nval : bool = (temp & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
     return 1;
 }
 
 fn cpx(con: &mut CpuExecution) -> u8
 {
-    let temp : u16 = con.rt_x as u16 - con.fetch as u16;
+    fetch(con);
+    let temp : u8 = con.rt_x.wrapping_sub(con.fetch);
 
     let cval : bool = con.rt_x >= con.fetch;
-    set_flag(CondType::CtZ, cval, con);
+    con.rt_sr.set(StatusFlags::C, cval);
 
-    let zval : bool = temp & 0x00FF == 0x0000;
-    set_flag(CondType::CtZ, zval, con);
+    let zval : bool = temp == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
 
-    let nval : bool = (temp & 0x0080) == 1;
-    set_flag(CondType::CtN, nval, con);
+    let nval : bool = (temp & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
     return 0;
 }
 
 fn cpy(con: &mut CpuExecution) -> u8
 {
-    let temp : u16 = con.rt_y as u16 - con.fetch as u16;
+    fetch(con);
+    let temp : u8 = con.rt_y.wrapping_sub(con.fetch);
 
     let cval : bool = con.rt_y >= con.fetch;
-    set_flag(CondType::CtZ, cval, con);
+    con.rt_sr.set(StatusFlags::C, cval);
 
-    let zval : bool = temp & 0x00FF == 0x0000;
-    set_flag(CondType::CtZ, zval, con);
+    let zval : bool = temp == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
 
-    let nval : bool = (temp & 0x0080) == 1;
-    set_flag(CondType::CtN, nval, con);
+    let nval : bool = (temp & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
     return 0;
 }
 
 fn dec(con: &mut CpuExecution) -> u8
 {
-    let temp : u8 = con.fetch - 1;
+    fetch(con);
+    let temp : u8 = con.fetch.wrapping_sub(1);
 
-    cpu_write(con.addr_abs, temp & 0x00FF);
+    write_back(con, temp);
 
-    let zval : bool = temp & 0x00FF == 0x0000;
-    set_flag(CondType::CtZ, zval, con);
+    let zval : bool = temp == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
 
-    let nval : bool = (temp & 0x0080) == 1;
-    set_flag(CondType::CtN, nval, con);
+    let nval : bool = (temp & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
     return 0;
 
 }
 
 fn dex(con: &mut CpuExecution) -> u8
 {
-    con.rt_x = con.rt_x - 1;
+    con.rt_x = con.rt_x.wrapping_sub(1);
 
-    let zval : bool = (con.rt_x == 0x00) == true;
-    set_flag(CondType::CtZ, zval,con);
+    let zval : bool = con.rt_x == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
 
-    let nval : bool = (con.rt_ac & 0x80) == 1;
-    set_flag(CondType::CtN, nval, con);
+    let nval : bool = (con.rt_x & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
     return 0;
     
 }
 
 fn dey(con: &mut CpuExecution) -> u8
 {
-    con.rt_y = con.rt_y - 1;
+    con.rt_y = con.rt_y.wrapping_sub(1);
 
-    let zval : bool = (con.rt_y == 0x00) == true;
-    set_flag(CondType::CtZ, zval,con);
+    let zval : bool = con.rt_y == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
 
-    let nval : bool = (con.rt_ac & 0x80) == 1;
-    set_flag(CondType::CtN, nval, con);
+    let nval : bool = (con.rt_y & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
     return 0;
 }
 
 fn eor(con: &mut CpuExecution) -> u8
 {
-    con.rt_ac = con.rt_ac ^ con.fetch;
+    fetch(con);
+    con.rt_ac ^= con.fetch;
 
-    set_flag(CondType::CtZ, con.rt_ac== 0x00, con);
-    set_flag(CondType::CtN, con.rt_ac & 0x80 == 1, con);
+    con.rt_sr.set(StatusFlags::Z, con.rt_ac == 0x00);
+    con.rt_sr.set(StatusFlags::N, (con.rt_ac & 0x80) != 0);
     return 1;
 }
 
 fn inc(con: &mut CpuExecution) -> u8
 {
-    let temp : u8 = con.fetch + 1;
-    cpu_write(con.addr_abs, temp & 0x00FF);
+    fetch(con);
+    let temp : u8 = con.fetch.wrapping_add(1);
+    write_back(con, temp);
 
-    let zval : bool = (temp & 0x00FF) == 0x0000;
-    set_flag(CondType::CtZ, zval, con);
+    let zval : bool = temp == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
 
-    let nval : bool = (temp & 0x0080) == 1;
-    set_flag(CondType::CtN, nval, con);
+    let nval : bool = (temp & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
 
     return 0;
 }
 
 fn inx(con: &mut CpuExecution) -> u8
 {
-    con.rt_x = con.rt_x + 1;
+    con.rt_x = con.rt_x.wrapping_add(1);
     
     let zval : bool = con.rt_x == 0x00;
-    set_flag(CondType::CtZ, zval, con);
+    con.rt_sr.set(StatusFlags::Z, zval);
 
-    let nval : bool = (con.rt_x & 0x80) == 1;
-    set_flag(CondType::CtN, nval, con);
+    let nval : bool = (con.rt_x & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
     return 0;
 }
 
 fn iny(con: &mut CpuExecution) -> u8
 {
-    con.rt_y = con.rt_y + 1;
-    
-    let zval : bool = con.rt_y == 0x00;
-    set_flag(CondType::CtZ, zval, con);
+    con.rt_y = con.rt_y.wrapping_add(1);
+    
+    let zval : bool = con.rt_y == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    let nval : bool = (con.rt_y & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
+    return 0;
+}
+
+fn jmp(con: &mut CpuExecution) -> u8
+{
+    con.rt_pc = con.addr_abs;
+    return 0;
+}
+
+// the low byte of the target comes in before the pushes and the high byte
+// after them, so the return address pushed is that of the high byte.
+fn jsr(con: &mut CpuExecution) -> u8
+{
+    let lo : u16 = read_pc(con);
+    dummy_read(con, 0x0100 + con.rt_sp as u16);
+
+    stack_push(con, ((con.rt_pc >> 8) & 0x00FF) as u8);
+    stack_push(con, (con.rt_pc & 0x00FF) as u8);
+
+    let hi : u16 = read_pc(con);
+    con.addr_abs = (hi << 8) | lo;
+
+    con.rt_pc = con.addr_abs;
+    return 0;
+}
+
+fn lda(con: &mut CpuExecution) -> u8
+{
+    fetch(con);
+    con.rt_ac = con.fetch;
+
+    let zval : bool = con.rt_ac == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    let nval : bool = (con.rt_ac & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
+
+    return 1;
+}
+
+fn ldx(con: &mut CpuExecution) -> u8
+{
+    fetch(con);
+    con.rt_x = con.fetch;
+
+    let zval : bool = con.rt_x == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    let nval : bool = (con.rt_x & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
+    return 1;
+}
+
+fn ldy(con: &mut CpuExecution) -> u8
+{
+    fetch(con);
+    con.rt_y = con.fetch;
+
+    let zval : bool = con.rt_y == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    let nval : bool = (con.rt_y & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
+    return 1;
+}
+
+fn lsr(con: &mut CpuExecution) -> u8
+{
+    fetch(con);
+    let cval : bool = (con.fetch & 0x01) != 0;
+    con.rt_sr.set(StatusFlags::C, cval);
+
+    let temp : u8 = con.fetch >> 1;
+
+    let zval : bool = temp == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    let nval : bool = (temp & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
+
+    write_back(con, temp);
+
+    return shift_penalty(con);
+}
+
+fn nop(con: &mut CpuExecution) -> u8
+{
+    // the undocumented forms with an operand read it like a load does, and
+    // the abs,X ones pay for a page cross.
+    fetch(con);
+
+    // $5C on the 65C02 keeps reading from $FF and the operand low byte for
+    // another four cycles.
+    if con.variant == CpuVariant::Wdc65C02 && current(con).cycles == 8
+    {
+        for _ in 0..4
+        {
+            dummy_read(con, 0xFF00 | (con.addr_abs & 0x00FF));
+        }
+    }
+    return 1;
+}
+
+fn ora(con: &mut CpuExecution) -> u8
+{
+    fetch(con);
+    con.rt_ac |= con.fetch;
+
+    let zval : bool = con.rt_ac == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    let nval : bool = (con.rt_ac & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
+
+    return 1;
+}
+
+fn pla(con: &mut CpuExecution) -> u8
+{
+    stack_wait(con);
+    con.rt_ac = stack_pull(con);
+
+    let zval : bool = con.rt_ac == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    let nval : bool = (con.rt_ac & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
+
+    return 0;
+}
+
+fn pha(con: &mut CpuExecution) -> u8
+{
+    stack_push(con, con.rt_ac);
+    return 0;
+  
+}
+
+fn php(con: &mut CpuExecution) -> u8
+{
+    stack_push(con, con.rt_sr.pushed(true));
+    return 0;
+}
+
+fn phx(con: &mut CpuExecution) -> u8
+{
+    stack_push(con, con.rt_x);
+    return 0;
+}
+
+fn phy(con: &mut CpuExecution) -> u8
+{
+    stack_push(con, con.rt_y);
+    return 0;
+}
+
+fn plp(con: &mut CpuExecution) -> u8
+{
+    stack_wait(con);
+    con.rt_sr = StatusFlags::pulled(stack_pull(con));
+    return 0;
+}
+
+fn plx(con: &mut CpuExecution) -> u8
+{
+    stack_wait(con);
+    con.rt_x = stack_pull(con);
+
+    let zval : bool = con.rt_x == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    let nval : bool = (con.rt_x & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
+
+    return 0;
+}
+
+fn ply(con: &mut CpuExecution) -> u8
+{
+    stack_wait(con);
+    con.rt_y = stack_pull(con);
+
+    let zval : bool = con.rt_y == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    let nval : bool = (con.rt_y & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
+
+    return 0;
+}
+
+fn rmb(con: &mut CpuExecution) -> u8
+{
+    fetch(con);
+    write_back(con, con.fetch & !opcode_bit(con));
+    return 0;
+}
+
+fn rol(con: &mut CpuExecution) -> u8
+{
+    fetch(con);
+    let temp : u16 = ((con.fetch as u16) << 1) | con.rt_sr.carry() as u16;
+
+    let cval : bool = (temp & 0xFF00) != 0;
+    con.rt_sr.set(StatusFlags::C, cval);
+
+    let zval : bool = (temp & 0x00FF) == 0x0000;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    let nval : bool = (temp & 0x0080) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
+
+    write_back(con, temp as u8);
+
+    return shift_penalty(con);
+
+}
+
+fn ror(con: &mut CpuExecution) -> u8
+{
+    fetch(con);
+    let temp : u8 = (con.rt_sr.carry() << 7) | (con.fetch >> 1);
+
+    let cval : bool = (con.fetch & 0x01) != 0;
+    con.rt_sr.set(StatusFlags::C, cval);
+
+    let zval : bool = temp == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    let nval : bool = (temp & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
+
+    write_back(con, temp);
+
+    return shift_penalty(con);
+}
+
+fn rti(con: &mut CpuExecution) -> u8
+{
+    stack_wait(con);
+    con.rt_sr = StatusFlags::pulled(stack_pull(con));
+
+    con.rt_pc = stack_pull(con) as u16;
+    con.rt_pc |= (stack_pull(con) as u16) << 8;
+    return 0;
+}
+
+fn rts(con: &mut CpuExecution) -> u8
+{
+    stack_wait(con);
+    con.rt_pc = stack_pull(con) as u16;
+    con.rt_pc |= (stack_pull(con) as u16) << 8;
+
+    // one more cycle reading the pulled address while PC is incremented.
+    dummy_read(con, con.rt_pc);
+    con.rt_pc = con.rt_pc.wrapping_add(1);
+    return 0;
+}
+
+fn sbc(con: &mut CpuExecution) -> u8
+{
+    fetch(con);
+    subtract_with_carry(con, con.fetch);
+    return 1;
+}
+
+fn sec(con: &mut CpuExecution) -> u8
+{
+    con.rt_sr.set(StatusFlags::C, true);
+    return 0;
+}
+
+fn sed(con: &mut CpuExecution) -> u8
+{
+    con.rt_sr.set(StatusFlags::D, true);
+    return 0;
+}
+
+fn sei(con: &mut CpuExecution) -> u8
+{
+    con.rt_sr.set(StatusFlags::I, true);
+    return 0;
+}
+
+fn smb(con: &mut CpuExecution) -> u8
+{
+    fetch(con);
+    write_back(con, con.fetch | opcode_bit(con));
+    return 0;
+}
+
+// STP and WAI spend one more cycle reading after the opcode before the
+// clock stops.
+fn stp(con: &mut CpuExecution) -> u8
+{
+    dummy_read(con, con.rt_pc);
+    con.state = SystemState::Stopped;
+    return 0;
+}
+
+fn wai(con: &mut CpuExecution) -> u8
+{
+    dummy_read(con, con.rt_pc);
+    con.state = SystemState::Waiting;
+    return 0;
+}
+
+fn sta(con: &mut CpuExecution) -> u8
+{
+    cpu_write(con, con.addr_abs, con.rt_ac);
+    return 0;
+}
+
+fn stx(con: &mut CpuExecution) -> u8
+{
+    cpu_write(con, con.addr_abs, con.rt_x);
+    return 0;
+}
+
+fn sty(con: &mut CpuExecution) -> u8
+{
+    cpu_write(con, con.addr_abs, con.rt_y);
+    return 0;
+}
+
+fn stz(con: &mut CpuExecution) -> u8
+{
+    cpu_write(con, con.addr_abs, 0x00);
+    return 0;
+}
+
+fn tax(con: &mut CpuExecution) -> u8
+{
+    con.rt_x = con.rt_ac;
+    
+    let zval : bool = con.rt_x == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    let nval : bool = (con.rt_x & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
+
+    return 0;
+}
+
+fn tay(con: &mut CpuExecution) -> u8
+{
+    con.rt_y = con.rt_ac;
+    
+    let zval : bool = con.rt_y == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    let nval : bool = (con.rt_y & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
+
+    return 0;
+}
+
+// TRB and TSB set Z like BIT does, from A AND the operand as it was, then
+// clear or set the bits of A in the operand.
+fn trb(con: &mut CpuExecution) -> u8
+{
+    fetch(con);
+
+    let zval : bool = (con.rt_ac & con.fetch) == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    write_back(con, con.fetch & !con.rt_ac);
+    return 0;
+}
+
+fn tsb(con: &mut CpuExecution) -> u8
+{
+    fetch(con);
+
+    let zval : bool = (con.rt_ac & con.fetch) == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    write_back(con, con.fetch | con.rt_ac);
+    return 0;
+}
+
+fn tsx(con: &mut CpuExecution) -> u8
+{  
+    con.rt_x = con.rt_sp;
+    
+    let zval : bool = con.rt_x == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    let nval : bool = (con.rt_x & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
+
+    return 0;
+}
+
+fn txa(con: &mut CpuExecution) -> u8
+{
+    con.rt_ac = con.rt_x;
+
+    let zval : bool = con.rt_ac == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    let nval : bool = (con.rt_ac & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
 
-    let nval : bool = (con.rt_y & 0x80) == 1;
-    set_flag(CondType::CtN, nval, con);
     return 0;
 }
 
-fn jmp(con: &mut CpuExecution) -> u8
+fn txs(con: &mut CpuExecution) -> u8
 {
-    con.rt_pc = con.addr_abs;
+    con.rt_sp = con.rt_x;
     return 0;
 }
 
-fn jsr(con: &mut CpuExecution) -> u8
+fn tya(con: &mut CpuExecution) -> u8
 {
+    con.rt_ac = con.rt_y;
 
-    con.rt_pc = con.rt_pc - 1;
+    let zval : bool = con.rt_ac == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
 
-    cpu_write((0x100 + con.rt_sp) as u16, ((con.rt_pc >> 8) & 0x00FF) as u8);
-    con.rt_sp = con.rt_sp - 1;
-    cpu_write((0x100 + con.rt_sp) as u16, ((con.rt_pc as u16) & 0x00FF) as u8);
-    con.rt_sp = con.rt_sp - 1;
+    let nval : bool = (con.rt_ac & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
 
-    con.rt_pc = con.addr_abs;
     return 0;
+
 }
 
+// Undocumented NMOS opcodes. Most of them are two documented operations
+// glued together because the decode ROM fires both at once.
 
-fn lda(con: &mut CpuExecution) -> u8
+// ASL oper + ORA oper
+fn slo(con: &mut CpuExecution) -> u8
 {
-    con.rt_ac = con.fetch;
+    fetch(con);
+
+    let cval : bool = (con.fetch & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::C, cval);
+
+    let temp : u8 = con.fetch << 1;
+    write_back(con, temp);
+
+    con.rt_ac |= temp;
 
     let zval : bool = con.rt_ac == 0x00;
-    set_flag(CondType::CtZ, zval, con);
+    con.rt_sr.set(StatusFlags::Z, zval);
 
-    let nval : bool = (con.rt_ac & 0x80) == 1;
-    set_flag(CondType::CtN, nval, con);
+    let nval : bool = (con.rt_ac & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
 
-    return 1;
+    return 0;
 }
 
-fn ldx(con: &mut CpuExecution) -> u8
+// ROL oper + AND oper
+fn rla(con: &mut CpuExecution) -> u8
 {
-    con.rt_x = con.fetch;
+    fetch(con);
+
+    let temp : u8 = (con.fetch << 1) | con.rt_sr.carry();
+
+    let cval : bool = (con.fetch & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::C, cval);
+
+    write_back(con, temp);
+
+    con.rt_ac &= temp;
 
     let zval : bool = con.rt_ac == 0x00;
-    set_flag(CondType::CtZ, zval, con);
+    con.rt_sr.set(StatusFlags::Z, zval);
 
-    let nval : bool = (con.rt_ac & 0x80) == 1;
-    set_flag(CondType::CtN, nval, con);
-    return 1;
+    let nval : bool = (con.rt_ac & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
+
+    return 0;
 }
 
-fn ldy(con: &mut CpuExecution) -> u8
+// LSR oper + EOR oper
+fn sre(con: &mut CpuExecution) -> u8
 {
-    con.rt_y = con.fetch;
+    fetch(con);
+
+    let cval : bool = (con.fetch & 0x01) != 0;
+    con.rt_sr.set(StatusFlags::C, cval);
+
+    let temp : u8 = con.fetch >> 1;
+    write_back(con, temp);
+
+    con.rt_ac ^= temp;
 
     let zval : bool = con.rt_ac == 0x00;
-    set_flag(CondType::CtZ, zval, con);
+    con.rt_sr.set(StatusFlags::Z, zval);
 
-    let nval : bool = (con.rt_ac & 0x80) == 1;
-    set_flag(CondType::CtN, nval, con);
-    return 1;
+    let nval : bool = (con.rt_ac & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
+
+    return 0;
 }
 
-fn lsr(con: &mut CpuExecution) -> u8
+// ROR oper + ADC oper, the carry out of the rotate feeds the add.
+fn rra(con: &mut CpuExecution) -> u8
 {
-    let cval : bool = (con.fetch & 0x0001) == 1;
-    set_flag(CondType::CtC, cval, con);
+    fetch(con);
 
-    let temp : u8 = con.fetch >> 1;
+    let temp : u8 = (con.rt_sr.carry() << 7) | (con.fetch >> 1);
 
-    let zval : bool = (temp & 0x00FF) == 0x0000;
-    set_flag(CondType::CtZ, zval, con);
+    let cval : bool = (con.fetch & 0x01) != 0;
+    con.rt_sr.set(StatusFlags::C, cval);
 
-    let nval : bool = temp & 0x0080 == 1;
-    set_flag(CondType::CtN, nval, con);
+    write_back(con, temp);
+
+    add_with_carry(con, temp);
 
     return 0;
 }
 
-fn nop() -> u8
+// store A AND X, no flags.
+fn sax(con: &mut CpuExecution) -> u8
 {
-   // match opcode
-    //{
-
-    //}
+    cpu_write(con, con.addr_abs, con.rt_ac & con.rt_x);
     return 0;
 }
 
-fn ora(con: &mut CpuExecution) -> u8
+// LDA oper + LDX oper
+fn lax(con: &mut CpuExecution) -> u8
 {
-    con.rt_ac = con.rt_ac | con.fetch;
+    fetch(con);
+    con.rt_ac = con.fetch;
+    con.rt_x = con.fetch;
 
     let zval : bool = con.rt_ac == 0x00;
-    set_flag(CondType::CtZ, zval, con);
+    con.rt_sr.set(StatusFlags::Z, zval);
 
-    let nval : bool = con.rt_ac & 0x80 == 1;
-    set_flag(CondType::CtN, nval, con);
+    let nval : bool = (con.rt_ac & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
 
     return 1;
-} 
+}
 
-fn pla(con: &mut CpuExecution) -> u8
+// DEC oper + CMP oper
+fn dcp(con: &mut CpuExecution) -> u8
 {
-    con.rt_sp = con.rt_sp + 1;
-    con.rt_ac = cpu_read_u8(0x0100 + con.rt_sp);
+    fetch(con);
 
-    let zval : bool = con.rt_ac == 0x00;
-    set_flag( CondType::CtZ, zval, con);
+    let temp : u8 = con.fetch.wrapping_sub(1);
+    write_back(con, temp);
 
-    let nval : bool = con.rt_ac & 0x80 == 1;
-    set_flag(CondType::CtN, nval, con);
+    let cval : bool = con.rt_ac >= temp;
+    con.rt_sr.set(StatusFlags::C, cval);
 
-    return 0;
-}
+    let zval : bool = con.rt_ac == temp;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    let nval : bool = (con.rt_ac.wrapping_sub(temp) & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
 
-fn pha(con: &mut CpuExecution) -> u8
-{
-    cpu_write(0x0100 +  con.rt_sp as u16, con.rt_ac);
-    con.rt_sp = con.rt_sp - 1;
     return 0;
-  
 }
 
-fn php(con: &mut CpuExecution) -> u8
+// INC oper + SBC oper
+fn isc(con: &mut CpuExecution) -> u8
 {
-    cpu_write(0x0100 +  con.rt_sp as u16, con.rt_sr | CondType::CtB | CondType::CtNone);
-    set_flag(CondType::CtB, false, con);
-    set_flag(CondType::CtNone, false, con);
+    fetch(con);
+
+    let temp : u8 = con.fetch.wrapping_add(1);
+    write_back(con, temp);
+
+    subtract_with_carry(con, temp);
+
     return 0;
 }
 
-fn plp(con: &mut CpuExecution) -> u8
+// AND oper, then bit 7 is copied into carry as if it had been shifted out.
+fn anc(con: &mut CpuExecution) -> u8
 {
-    con.rt_sp = con.rt_sp + 1;
-    con.rt_sr = cpu_read_u8(0x0100
-         + con.rt_sp);
-    set_flag(CondType::CtNone, true, con);
+    fetch(con);
+    con.rt_ac &= con.fetch;
+
+    let zval : bool = con.rt_ac == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    let nval : bool = (con.rt_ac & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
+    con.rt_sr.set(StatusFlags::C, nval);
+
     return 0;
 }
 
-fn rol(con: &mut CpuExecution) -> u8
+// AND oper + LSR A
+fn alr(con: &mut CpuExecution) -> u8
 {
-    let temp : u16 = (con.fetch << 1) | get_flag(CondType::CtC, con);
-
-    let cval : bool = (temp as u8) == 0x0001;
-    set_flag(CondType::CtC, cval, con);
-
-    let zval : bool = (temp as u8) == 0x0000;
-    set_flag(CondType::CtZ, zval, con);
+    fetch(con);
+    let temp : u8 = con.rt_ac & con.fetch;
 
-    let nval : bool = (temp & 0x0080) == 1;
-    set_flag(CondType::CtZ, zval, con);
+    let cval : bool = (temp & 0x01) != 0;
+    con.rt_sr.set(StatusFlags::C, cval);
 
-    if (inst::AddrMode == implied_addr(con))
-    {
-        con.rt_ac = temp as u8;
+    con.rt_ac = temp >> 1;
 
-    }
+    let zval : bool = con.rt_ac == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
 
-    else 
-    {
-        cpu_write(con.addr_abs, temp as u8);
-    }
+    con.rt_sr.set(StatusFlags::N, false);
 
     return 0;
-
 }
 
-fn ror(con: &mut CpuExecution) -> u8
+// AND oper + ROR A, but C and V come out of the adder rather than the
+// shifter: C is bit 6 of the result and V is bit 6 XOR bit 5. In decimal
+// mode the adder also applies a BCD style fix-up to each nibble.
+fn arr(con: &mut CpuExecution) -> u8
 {
-    let temp : u16 = get_flag(CondType::CtC, con) << 7 | (con.fetch >> 1) ;
+    fetch(con);
+    let temp : u8 = con.rt_ac & con.fetch;
+    let carry : u8 = con.rt_sr.carry();
+    let mut result : u8 = (carry << 7) | (temp >> 1);
 
-    let cval : bool = (con.fetch & 0x01);
-    set_flag(CondType::CtC, cval, con);
+    let zval : bool = result == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
 
-    let zval : bool = (temp as u8) == 0x00;
-    set_flag(CondType::CtZ, zval, con);
+    let nval : bool = carry == 1;
+    con.rt_sr.set(StatusFlags::N, nval);
 
-    let nval : bool = (temp & 0x0080);
-    set_flag(CondType::CtZ, zval, con);
+    let vval : bool = ((temp ^ result) & 0x40) != 0;
+    con.rt_sr.set(StatusFlags::V, vval);
 
-    if (inst::AddrMode == implied_addr(con))
+    if con.decimal_mode && con.rt_sr.contains(StatusFlags::D)
     {
-        con.rt_ac = temp as u8;
+        if (temp & 0x0F) + (temp & 0x01) > 0x05
+        {
+            result = (result & 0xF0) | (result.wrapping_add(0x06) & 0x0F);
+        }
 
-    }
+        let cval : bool = (temp as u16 & 0x00F0) + (temp as u16 & 0x0010) > 0x0050;
+        con.rt_sr.set(StatusFlags::C, cval);
 
-    else 
-    {
-        cpu_write(con.addr_abs, temp as u8);
+        if cval
+        {
+            result = result.wrapping_add(0x60);
+        }
     }
+    else
+    {
+        let cval : bool = (result & 0x40) != 0;
+        con.rt_sr.set(StatusFlags::C, cval);
 
-    return 0;
-}
+        let vval : bool = (((result >> 6) ^ (result >> 5)) & 0x01) != 0;
+        con.rt_sr.set(StatusFlags::V, vval);
+    }
 
-fn rti(con: &mut CpuExecution) -> u8
-{
-    con.rt_sp = con.rt_sp + 1;
-    con.rt_sr = cpu_read_u8(0x0100 + con.rt_sp);
-    //con.rt_sr &= !CondType::CtB;
-    //con.rt_sr &= !CondType::CtNone;
+    con.rt_ac = result;
 
-    con.rt_sp = con.rt_sp + 1;
-    con.rt_pc = cpu_read(0x100 + con.rt_sp as u16);
-    con.rt_sp = con.rt_sp + 1;
-    con.rt_pc = cpu_read(0x100 + con.rt_sp as u16) << 8;
     return 0;
 }
 
-fn rts(con: &mut CpuExecution) -> u8
+// X = (A AND X) - oper, a compare that keeps its result. No borrow in, no
+// decimal mode, and V is left alone.
+fn sbx(con: &mut CpuExecution) -> u8
 {
-    con.rt_sp = con.rt_sp + 1;
-    con.rt_sr = cpu_read_u8(0x0100 + con.rt_sp);
-    con.rt_sp = con.rt_sp + 1;
-    con.rt_pc |= (cpu_read_u8(0x0100 + con.rt_sp) << 8) as u16;
+    fetch(con);
+    let temp : u8 = con.rt_ac & con.rt_x;
 
-    con.rt_pc = con.rt_pc + 1;
-    return 0;
-}
+    let cval : bool = temp >= con.fetch;
+    con.rt_sr.set(StatusFlags::C, cval);
 
-fn sbc(con: &mut CpuExecution) -> u8
-{
-    return 1;
-}
+    con.rt_x = temp.wrapping_sub(con.fetch);
 
-fn sec(con: &mut CpuExecution) -> u8
-{
-    set_flag(CondType::CtC, true, con);
-    return 0;
-}
+    let zval : bool = con.rt_x == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
 
-fn sed(con: &mut CpuExecution) -> u8
-{
-    set_flag(CondType::CtD, true, con);
-    return 0;
-}
+    let nval : bool = (con.rt_x & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
 
-fn sei(con: &mut CpuExecution) -> u8
-{
-    set_flag(CondType::CtI, true, con);
     return 0;
 }
 
-fn sta(con: &mut CpuExecution) -> u8
+// (A OR magic) AND X AND oper. The magic constant depends on the chip and
+// is settable through con.magic.
+fn ane(con: &mut CpuExecution) -> u8
 {
-    cpu_write(con.addr_abs, con.rt_ac);
-    return 0;
-}
+    fetch(con);
+    con.rt_ac = (con.rt_ac | con.magic) & con.rt_x & con.fetch;
 
-fn stx(con: &mut CpuExecution) -> u8
-{
-    cpu_write(con.addr_abs, con.rt_x);
-    return 0;
-}
+    let zval : bool = con.rt_ac == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    let nval : bool = (con.rt_ac & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
 
-fn sty(con: &mut CpuExecution) -> u8
-{
-    cpu_write(con.addr_abs, con.rt_y);
     return 0;
 }
 
-fn tax(con: &mut CpuExecution) -> u8
+// A = X = (A OR magic) AND oper, with the same magic constant as ANE.
+fn lxa(con: &mut CpuExecution) -> u8
 {
+    fetch(con);
+    con.rt_ac = (con.rt_ac | con.magic) & con.fetch;
     con.rt_x = con.rt_ac;
-    
-    let zval : bool = con.rt_x == 0x00;
-    set_flag(CondType::CtZ, zval, con);
 
-    let nval : bool = (con.rt_x & 0x80) == 1;
-    set_flag(CondType::CtN, nval, con);
+    let zval : bool = con.rt_ac == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
+
+    let nval : bool = (con.rt_ac & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
 
     return 0;
 }
 
-fn tay(con: &mut CpuExecution) -> u8
+// A = X = SP = oper AND SP
+fn las(con: &mut CpuExecution) -> u8
 {
-    con.rt_y = con.rt_ac;
-    
-    let zval : bool = (con.rt_y == 0x00);
-    set_flag(CondType::CtZ, zval, con);
-
-    let nval : bool = (con.rt_y & 0x80) == 1;
-    set_flag(CondType::CtN, nval, con);
+    fetch(con);
+    let temp : u8 = con.fetch & con.rt_sp;
+    con.rt_ac = temp;
+    con.rt_x = temp;
+    con.rt_sp = temp;
 
-    return 0;
-}
-
-fn tsx(con: &mut CpuExecution) -> u8
-{  
-    con.rt_x = con.rt_sp;
-    
-    let zval : bool = (con.rt_x == 0x00);
-    set_flag(CondType::CtZ, zval, con);
+    let zval : bool = temp == 0x00;
+    con.rt_sr.set(StatusFlags::Z, zval);
 
-    let nval : bool = (con.rt_x & 0x80) == 1;
-    set_flag(CondType::CtN, nval, con);
+    let nval : bool = (temp & 0x80) != 0;
+    con.rt_sr.set(StatusFlags::N, nval);
 
-    return 0;
+    return 1;
 }
 
-fn txa(con: &mut CpuExecution) -> u8
+// SHA, SHX, SHY and TAS store their value ANDed with the high byte of the
+// base address plus one. When the index carries into the next page the
+// fixed-up high byte never reaches the bus, the stored value ends up there
+// in its place.
+fn unstable_store(con: &mut CpuExecution, value: u8, index: u8)
 {
-    con.rt_ac = con.rt_x;
+    let base : u16 = con.addr_abs.wrapping_sub(index as u16);
+    let hi : u8 = ((base >> 8) as u8).wrapping_add(1);
+    let data : u8 = value & hi;
 
-    let zval : bool = (con.rt_ac == 0x00);
-    set_flag(CondType::CtZ, zval, con);
+    let mut addr : u16 = con.addr_abs;
+    if (base & 0xFF00) != (con.addr_abs & 0xFF00)
+    {
+        addr = ((data as u16) << 8) | (addr & 0x00FF);
+    }
 
-    let nval : bool = (con.rt_ac & 0x80) == 1;
-    set_flag(CondType::CtN, nval, con);
+    cpu_write(con, addr, data);
+}
 
+// store A AND X AND (H + 1)
+fn sha(con: &mut CpuExecution) -> u8
+{
+    unstable_store(con, con.rt_ac & con.rt_x, con.rt_y);
     return 0;
 }
 
-fn txs(con: &mut CpuExecution) -> u8
+// store X AND (H + 1)
+fn shx(con: &mut CpuExecution) -> u8
 {
-    con.rt_sp = con.rt_x;
+    unstable_store(con, con.rt_x, con.rt_y);
     return 0;
 }
 
-fn tya(con: &mut CpuExecution) -> u8
+// store Y AND (H + 1)
+fn shy(con: &mut CpuExecution) -> u8
 {
-    con.rt_ac = con.rt_y;
-
-    let zval : bool = (con.rt_ac == 0x00);
-    set_flag(CondType::CtZ, zval, con);
-
-    let nval : bool = (con.rt_ac & 0x80) == 1;
-    set_flag(CondType::CtN, nval, con);
-
+    unstable_store(con, con.rt_y, con.rt_x);
     return 0;
-
 }
 
-fn illegal_opcode() -> u8
+// SP = A AND X, then store SP AND (H + 1)
+fn tas(con: &mut CpuExecution) -> u8
 {
+    con.rt_sp = con.rt_ac & con.rt_x;
+    unstable_store(con, con.rt_sp, con.rt_y);
     return 0;
 }
 
-pub fn get_flag(flag: CondType, con: &mut CpuExecution) -> u8
+// $EB, an exact copy of SBC #oper.
+fn usbc(con: &mut CpuExecution) -> u8
 {
-    match flag
-    {
-       CondType::CtN    => return if (con.rt_sr & 1 << 7) > 0 {1} else {0},
-       CondType::CtV    => return if (con.rt_sr & 1 << 6) > 0 {1} else {0},
-       CondType::CtNone => 0,
-       CondType::CtB    => return if (con.rt_sr & 1 << 4) > 0 {1} else {0},
-       CondType::CtD    => return if (con.rt_sr & 1 << 3) > 0 {1} else {0},
-       CondType::CtI    => return if (con.rt_sr & 1 << 2) > 0 {1} else {0},
-       CondType::CtZ    => return if (con.rt_sr & 1 << 1) > 0 {1} else {0},
-       CondType::CtC    => return if (con.rt_sr & 1 << 0) > 0 {1} else {0},
-    }
+    fetch(con);
+    subtract_with_carry(con, con.fetch);
+    return 0;
 }
 
-pub fn set_flag(flag: CondType, val: bool, con: &mut CpuExecution) -> SystemState
+// JAM stops the CPU dead after the opcode fetch, which is the one cycle it
+// is charged. Nothing more is fetched and only a reset brings it back.
+// clock() goes on making the reads the chip keeps making while jammed,
+// step() returns at once without using any cycles. With jam_trap set the opcode is instead
+// reported to the host once, so it can be used as an emulator hook.
+fn jam(con: &mut CpuExecution) -> u8
 {
-    
-    match flag
-    {
-       CondType::CtN    => if val == true {con.rt_sr|= 1 << 7;} else {con.rt_sr &= !(1 << 7)},
-       CondType::CtV    => if val == true {con.rt_sr |= 1 << 6;} else {con.rt_sr &= !(1 << 6)},
-       CondType::CtNone => if val == true {return SystemState::None}
-       CondType::CtB    => if val == true {con.rt_sr |= 1 << 4;} else {con.rt_sr &= !(1 << 4)}
-       CondType::CtD    => if val == true {con.rt_sr |= 1 << 3;} else {con.rt_sr &= !(1 << 3)}
-       CondType::CtI    => if val == true {con.rt_sr |= 1 << 2;} else {con.rt_sr &= !(1 << 2)}
-       CondType::CtZ    => if val == true {con.rt_sr |= 1 << 1;} else {con.rt_sr &= !(1 << 1)}
-       CondType::CtC    => if val == true {con.rt_sr |= 1 << 0;} else {con.rt_sr &= !(1 << 0)}
+    let pc : u16 = con.rt_pc.wrapping_sub(1);
+
+    if con.jam_trap
+    {
+        con.state = SystemState::Trap { pc, opcode: con.opcode };
+    }
+    else
+    {
+        con.state = SystemState::Jam { pc, opcode: con.opcode };
     }
 
-    return SystemState::CpuSetFlag;
+    return 0;
 }
 
 // configure the processor instruction we need for the CPU.
-pub fn match_process(inst_type: &inst::InstructionType, con: &mut CpuExecution) -> u8
+pub(crate) fn match_process(inst_type: &inst::InstructionType, con: &mut CpuExecution) -> u8
 {
     match inst_type
     {
         inst::InstructionType::ADC => adc(con), //legal
-        inst::InstructionType::ANC => illegal_opcode(),
+        inst::InstructionType::ANC => anc(con),
         inst::InstructionType::AND => and(con),
-        inst::InstructionType::ANE => illegal_opcode(),
-        inst::InstructionType::ALR => illegal_opcode(), //illegal
-        inst::InstructionType::ARR => illegal_opcode(),
+        inst::InstructionType::ANE => ane(con),
+        inst::InstructionType::ALR => alr(con),
+        inst::InstructionType::ARR => arr(con),
         inst::InstructionType::ASL => asl(con),
+        inst::InstructionType::BBR0 | inst::InstructionType::BBR1 | inst::InstructionType::BBR2 | inst::InstructionType::BBR3
+            | inst::InstructionType::BBR4 | inst::InstructionType::BBR5 | inst::InstructionType::BBR6 | inst::InstructionType::BBR7 => bbr(con),
+        inst::InstructionType::BBS0 | inst::InstructionType::BBS1 | inst::InstructionType::BBS2 | inst::InstructionType::BBS3
+            | inst::InstructionType::BBS4 | inst::InstructionType::BBS5 | inst::InstructionType::BBS6 | inst::InstructionType::BBS7 => bbs(con),
         inst::InstructionType::BCC => bcc(con),
         inst::InstructionType::BCS => bcs(con),
         inst::InstructionType::BEQ => beq(con),
@@ -990,6 +1865,7 @@ pub fn match_process(inst_type: &inst::InstructionType, con: &mut CpuExecution)
         inst::InstructionType::BMI => bmi(con),
         inst::InstructionType::BNE => bne(con),
         inst::InstructionType::BPL => bpl(con),
+        inst::InstructionType::BRA => bra(con),
         inst::InstructionType::BRK => brk(con),
         inst::InstructionType::BVC => bvc(con),
         inst::InstructionType::BVS => bvs(con),
@@ -1000,7 +1876,7 @@ pub fn match_process(inst_type: &inst::InstructionType, con: &mut CpuExecution)
         inst::InstructionType::CMP => cmp(con),
         inst::InstructionType::CPX => cpx(con),
         inst::InstructionType::CPY => cpy(con),
-        inst::InstructionType::DCP => illegal_opcode(),
+        inst::InstructionType::DCP => dcp(con),
         inst::InstructionType::DEC => dec(con),
         inst::InstructionType::DEX => dex(con),
         inst::InstructionType::DEY => dey(con),
@@ -1008,51 +1884,64 @@ pub fn match_process(inst_type: &inst::InstructionType, con: &mut CpuExecution)
         inst::InstructionType::INC => inc(con),
         inst::InstructionType::INX => inx(con),
         inst::InstructionType::INY => iny(con),
-        inst::InstructionType::ISC => illegal_opcode(),
-        inst::InstructionType::JAM => illegal_opcode(),
+        inst::InstructionType::ISC => isc(con),
+        inst::InstructionType::JAM => jam(con),
         inst::InstructionType::JMP => jmp(con),
         inst::InstructionType::JSR => jsr(con),
-        inst::InstructionType::LAS => illegal_opcode(),
-        inst::InstructionType::LAX => illegal_opcode(),
+        inst::InstructionType::LAS => las(con),
+        inst::InstructionType::LAX => lax(con),
         inst::InstructionType::LDA => lda(con),
         inst::InstructionType::LDX => ldx(con),
         inst::InstructionType::LDY => ldy(con),
         inst::InstructionType::LSR => lsr(con),
-        inst::InstructionType::LXA => illegal_opcode(),
-        inst::InstructionType::NOP => nop(),
+        inst::InstructionType::LXA => lxa(con),
+        inst::InstructionType::NOP => nop(con),
         inst::InstructionType::ORA => ora(con),
         inst::InstructionType::PHA => pha(con),
         inst::InstructionType::PHP => php(con),
+        inst::InstructionType::PHX => phx(con),
+        inst::InstructionType::PHY => phy(con),
         inst::InstructionType::PLA => pla(con),
         inst::InstructionType::PLP => plp(con),
-        inst::InstructionType::RLA => illegal_opcode(),
+        inst::InstructionType::PLX => plx(con),
+        inst::InstructionType::PLY => ply(con),
+        inst::InstructionType::RLA => rla(con),
         inst::InstructionType::ROL => rol(con),
         inst::InstructionType::ROR => ror(con),
-        inst::InstructionType::RRA => illegal_opcode(),
+        inst::InstructionType::RRA => rra(con),
         inst::InstructionType::RTI => rti(con),
         inst::InstructionType::RTS => rts(con),
-        inst::InstructionType::SAX => illegal_opcode(),
+        inst::InstructionType::RMB0 | inst::InstructionType::RMB1 | inst::InstructionType::RMB2 | inst::InstructionType::RMB3
+            | inst::InstructionType::RMB4 | inst::InstructionType::RMB5 | inst::InstructionType::RMB6 | inst::InstructionType::RMB7 => rmb(con),
+        inst::InstructionType::SAX => sax(con),
         inst::InstructionType::SBC => sbc(con),
-        inst::InstructionType::SBX => illegal_opcode(),
+        inst::InstructionType::SBX => sbx(con),
         inst::InstructionType::SEC => sec(con),
         inst::InstructionType::SED => sed(con),
         inst::InstructionType::SEI => sei(con),
-        inst::InstructionType::SHA => illegal_opcode(),
-        inst::InstructionType::SHX => illegal_opcode(),
-        inst::InstructionType::SHY => illegal_opcode(),
-        inst::InstructionType::SLO => illegal_opcode(),
-        inst::InstructionType::SRE => illegal_opcode(),
+        inst::InstructionType::SHA => sha(con),
+        inst::InstructionType::SHX => shx(con),
+        inst::InstructionType::SHY => shy(con),
+        inst::InstructionType::SLO => slo(con),
+        inst::InstructionType::SMB0 | inst::InstructionType::SMB1 | inst::InstructionType::SMB2 | inst::InstructionType::SMB3
+            | inst::InstructionType::SMB4 | inst::InstructionType::SMB5 | inst::InstructionType::SMB6 | inst::InstructionType::SMB7 => smb(con),
+        inst::InstructionType::SRE => sre(con),
         inst::InstructionType::STA => sta(con),
+        inst::InstructionType::STP => stp(con),
         inst::InstructionType::STX => stx(con),
         inst::InstructionType::STY => sty(con),
-        inst::InstructionType::TAS => illegal_opcode(),
+        inst::InstructionType::STZ => stz(con),
+        inst::InstructionType::TAS => tas(con),
         inst::InstructionType::TAX => tax(con),
         inst::InstructionType::TAY => tay(con),
+        inst::InstructionType::TRB => trb(con),
+        inst::InstructionType::TSB => tsb(con),
         inst::InstructionType::TSX => tsx(con),
         inst::InstructionType::TXA => txa(con),
         inst::InstructionType::TXS => txs(con),
         inst::InstructionType::TYA => tya(con),
-        inst::InstructionType::USBC => illegal_opcode()
+        inst::InstructionType::USBC => usbc(con),
+        inst::InstructionType::WAI => wai(con)
     }
 }
 
@@ -1060,12 +1949,13 @@ pub fn match_process(inst_type: &inst::InstructionType, con: &mut CpuExecution)
 // link addr mode function with the enum
 
 // configure the processor instruction we need for the CPU.
-pub fn match_addr(addr_type: &inst::AddrMode, con: &mut CpuExecution) -> u8
+pub(crate) fn match_addr(addr_type: &inst::AddrMode, con: &mut CpuExecution) -> u8
 {
     match addr_type
     {
        inst::AddrMode::A => implied_addr(con),
        inst::AddrMode::ABS => absolute_addr(con),
+       inst::AddrMode::AbsIndX => absolute_indexed_indirect_addr(con),
        inst::AddrMode::AbsX => absolute_x_addr(con),
        inst::AddrMode::AbsY => absolute_y_addr(con),
        inst::AddrMode::IMM => immediate_addr(con),
@@ -1076,14 +1966,387 @@ pub fn match_addr(addr_type: &inst::AddrMode, con: &mut CpuExecution) -> u8
        inst::AddrMode::JAM => jam_addr(),
        inst::AddrMode::REL => relative_addr(con),
        inst::AddrMode::ZPG => zero_page_addr(con),
+       inst::AddrMode::ZpgInd => zero_page_indirect_addr(con),
+       inst::AddrMode::ZpgRel => zero_page_relative_addr(con),
        inst::AddrMode::ZpgX => zero_page_x_addr(con),
        inst::AddrMode::ZpgY => zero_page_y_addr(con)
     }
     
 }
 
-pub fn inst_cycles(cycles: u8) -> u8
+pub(crate) fn inst_cycles(cycles: u8) -> u8
 {
     let cyc = cycles;
     return cyc;
 }
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::bus::{BusAccess, Ram};
+
+    const ADC_IMM : u8 = 0x69;
+    const SBC_IMM : u8 = 0xE9;
+
+    // run one ADC or SBC #operand with D set and hand back the CPU.
+    fn decimal(variant: CpuVariant, opcode: u8, a: u8, operand: u8, carry: bool) -> CpuExecution
+    {
+        let mut ram : Ram = Ram::new();
+        ram.load(0x0200, &[opcode, operand]);
+
+        let mut con : CpuExecution = CpuExecution::with_variant(Box::new(ram), variant);
+        con.rt_pc = 0x0200;
+        con.rt_ac = a;
+        con.rt_sr = StatusFlags::D | StatusFlags::U;
+        con.rt_sr.set(StatusFlags::C, carry);
+        con.step();
+        return con;
+    }
+
+    #[test]
+    fn decimal_add()
+    {
+        for variant in [CpuVariant::Nmos6502, CpuVariant::Wdc65C02]
+        {
+            let con : CpuExecution = decimal(variant, ADC_IMM, 0x58, 0x46, false);
+            assert_eq!(con.rt_ac, 0x04, "{:?}", variant);
+            assert!(con.rt_sr.contains(StatusFlags::C), "{:?}", variant);
+
+            let con : CpuExecution = decimal(variant, ADC_IMM, 0x12, 0x34, true);
+            assert_eq!(con.rt_ac, 0x47, "{:?}", variant);
+            assert!(!con.rt_sr.contains(StatusFlags::C), "{:?}", variant);
+        }
+    }
+
+    #[test]
+    fn decimal_subtract()
+    {
+        for variant in [CpuVariant::Nmos6502, CpuVariant::Wdc65C02]
+        {
+            let con : CpuExecution = decimal(variant, SBC_IMM, 0x40, 0x13, true);
+            assert_eq!(con.rt_ac, 0x27, "{:?}", variant);
+            assert!(con.rt_sr.contains(StatusFlags::C), "{:?}", variant);
+
+            let con : CpuExecution = decimal(variant, SBC_IMM, 0x00, 0x01, true);
+            assert_eq!(con.rt_ac, 0x99, "{:?}", variant);
+            assert!(!con.rt_sr.contains(StatusFlags::C), "{:?}", variant);
+        }
+    }
+
+    // digits above 9 go through the same nibble fix-ups, and the two chips
+    // part ways on how SBC borrows out of the low nibble.
+    #[test]
+    fn decimal_invalid_digits()
+    {
+        let con : CpuExecution = decimal(CpuVariant::Nmos6502, ADC_IMM, 0x0F, 0x0F, false);
+        assert_eq!(con.rt_ac, 0x14);
+        assert!(!con.rt_sr.contains(StatusFlags::C));
+
+        let con : CpuExecution = decimal(CpuVariant::Wdc65C02, ADC_IMM, 0x0F, 0x0F, false);
+        assert_eq!(con.rt_ac, 0x14);
+
+        let con : CpuExecution = decimal(CpuVariant::Nmos6502, SBC_IMM, 0x10, 0x0F, true);
+        assert_eq!(con.rt_ac, 0x0B);
+        assert!(con.rt_sr.contains(StatusFlags::C));
+
+        let con : CpuExecution = decimal(CpuVariant::Wdc65C02, SBC_IMM, 0x10, 0x0F, true);
+        assert_eq!(con.rt_ac, 0xFB);
+        assert!(con.rt_sr.contains(StatusFlags::C));
+    }
+
+    // NMOS takes Z from the binary sum and N from the half corrected one,
+    // the 65C02 takes both from the BCD result and spends a cycle on it.
+    #[test]
+    fn decimal_flags_nmos_and_cmos()
+    {
+        let nmos : CpuExecution = decimal(CpuVariant::Nmos6502, ADC_IMM, 0x99, 0x01, false);
+        let cmos : CpuExecution = decimal(CpuVariant::Wdc65C02, ADC_IMM, 0x99, 0x01, false);
+        assert_eq!(nmos.rt_ac, 0x00);
+        assert_eq!(cmos.rt_ac, 0x00);
+        assert!(!nmos.rt_sr.contains(StatusFlags::Z));
+        assert!(cmos.rt_sr.contains(StatusFlags::Z));
+
+        let nmos : CpuExecution = decimal(CpuVariant::Nmos6502, ADC_IMM, 0x58, 0x46, false);
+        let cmos : CpuExecution = decimal(CpuVariant::Wdc65C02, ADC_IMM, 0x58, 0x46, false);
+        assert!(nmos.rt_sr.contains(StatusFlags::N));
+        assert!(!cmos.rt_sr.contains(StatusFlags::N));
+
+        let nmos : CpuExecution = decimal(CpuVariant::Nmos6502, SBC_IMM, 0x00, 0x01, true);
+        let cmos : CpuExecution = decimal(CpuVariant::Wdc65C02, SBC_IMM, 0x00, 0x01, true);
+        assert!(nmos.rt_sr.contains(StatusFlags::N));
+        assert!(cmos.rt_sr.contains(StatusFlags::N));
+
+        assert_eq!(nmos.clock_count, 2);
+        assert_eq!(cmos.clock_count, 3);
+    }
+
+    // the 2A03 ignores D altogether.
+    #[test]
+    fn decimal_disabled_on_2a03()
+    {
+        let con : CpuExecution = decimal(CpuVariant::Ricoh2A03, ADC_IMM, 0x09, 0x01, false);
+        assert_eq!(con.rt_ac, 0x0A);
+    }
+
+    // STP, then skipping far enough to run into the end of the clock count.
+    #[test]
+    fn fast_forward_saturates()
+    {
+        let mut ram : Ram = Ram::new();
+        ram.load(0x0200, &[0xDB]);
+
+        let mut con : CpuExecution = CpuExecution::with_variant(Box::new(ram), CpuVariant::Wdc65C02);
+        con.rt_pc = 0x0200;
+        assert_eq!(con.fast_forward(100), 0);
+
+        con.step();
+        assert_eq!(con.state, SystemState::Stopped);
+        let stopped_at : u64 = con.clock_count;
+        assert_eq!(con.fast_forward(100), 100);
+        assert_eq!(con.clock_count, stopped_at + 100);
+
+        con.clock_count = u64::MAX - 10;
+        assert_eq!(con.fast_forward(100), 10);
+        assert_eq!(con.clock_count, u64::MAX);
+    }
+
+    // WAI, then skip to the end of the clock count as a host sleeping until
+    // the next interrupt would. Waking up and tracing must not overflow.
+    #[test]
+    fn step_after_saturation()
+    {
+        let mut ram : Ram = Ram::new();
+        ram.load(0x0200, &[0xCB, 0xEA]);
+
+        let mut con : CpuExecution = CpuExecution::with_variant(Box::new(ram), CpuVariant::Wdc65C02);
+        con.rt_pc = 0x0200;
+        con.rt_sr = StatusFlags::I | StatusFlags::U;
+        con.step();
+        assert_eq!(con.state, SystemState::Waiting);
+
+        con.fast_forward(u64::MAX);
+        con.set_irq(true);
+        con.trace = Some(Box::new(Vec::new()));
+        con.step();
+        assert_eq!(con.rt_pc, 0x0202);
+        assert_eq!(con.clock_count, u64::MAX);
+
+        con.clock();
+        assert_eq!(con.clock_count, u64::MAX);
+        assert!(con.trace_line().ends_with(&format!("CYC:{}", u64::MAX)));
+    }
+
+    // a JAM costs its opcode fetch, and clocking it on keeps the bus busy.
+    #[test]
+    fn jam_cycles()
+    {
+        let mut ram : Ram = Ram::new();
+        ram.load(0x0200, &[0x02]);
+
+        let reads : Rc<RefCell<Vec<u16>>> = Rc::new(RefCell::new(Vec::new()));
+        let log = reads.clone();
+
+        let mut con : CpuExecution = CpuExecution::new(Box::new(ram));
+        con.rt_pc = 0x0200;
+        con.cycle_hook = Some(Box::new(move |addr, _, _| log.borrow_mut().push(addr)));
+
+        con.step();
+        assert_eq!(con.state, SystemState::Jam { pc: 0x0200, opcode: 0x02 });
+        assert_eq!(con.clock_count, 1);
+        assert_eq!(*reads.borrow(), vec![0x0200]);
+
+        con.step();
+        assert_eq!(con.clock_count, 1);
+
+        con.clock();
+        con.clock();
+        assert_eq!(con.clock_count, 3);
+        assert_eq!(*reads.borrow(), vec![0x0200, 0xFFFF, 0xFFFF]);
+    }
+
+    // an NMOS CPU at $0200 with I clear, IRQ vectored to $0300 and NMI to
+    // $0400.
+    fn interrupts(bus: Box<dyn Bus>) -> CpuExecution
+    {
+        let mut con : CpuExecution = CpuExecution::new(bus);
+        con.bus.write(0xFFFA, 0x00);
+        con.bus.write(0xFFFB, 0x04);
+        con.bus.write(0xFFFE, 0x00);
+        con.bus.write(0xFFFF, 0x03);
+        con.rt_pc = 0x0200;
+        con.rt_sp = 0xFD;
+        con.rt_sr = StatusFlags::U;
+        return con;
+    }
+
+    fn program(code: &[u8]) -> Box<Ram>
+    {
+        let mut ram : Ram = Ram::new();
+        ram.load(0x0200, code);
+        return Box::new(ram);
+    }
+
+    // CLI clears I too late for its own poll, the IRQ waits for the NOP.
+    #[test]
+    fn irq_after_cli_is_one_instruction_late()
+    {
+        let mut con : CpuExecution = interrupts(program(&[0x58, 0xEA, 0xEA]));
+        con.rt_sr = StatusFlags::I | StatusFlags::U;
+        con.set_irq(true);
+
+        assert_eq!(con.step(), SystemState::CpuInst);
+        assert_eq!(con.step(), SystemState::CpuInst);
+        assert_eq!(con.rt_pc, 0x0202);
+        assert_eq!(con.step(), SystemState::CpuIrq);
+        assert_eq!(con.rt_pc, 0x0300);
+        assert_eq!(con.bus.peek(0x01FC), 0x02);
+    }
+
+    // an IRQ that comes up while SEI runs is still taken after it.
+    #[test]
+    fn irq_pending_through_sei()
+    {
+        let mut con : CpuExecution = interrupts(program(&[0xEA, 0x78, 0xEA]));
+
+        con.step();
+        con.step();
+        assert!(con.rt_sr.contains(StatusFlags::I));
+
+        con.set_irq(true);
+        assert_eq!(con.step(), SystemState::CpuIrq);
+        assert_eq!(con.rt_pc, 0x0300);
+        assert_eq!(con.bus.peek(0x01FB) & StatusFlags::I.bits(), StatusFlags::I.bits());
+    }
+
+    // a taken branch that stays on its page lets one more instruction run
+    // before the IRQ, one not taken does not.
+    #[test]
+    fn irq_after_taken_branch()
+    {
+        // LDA #$00, BEQ to the next opcode, NOP
+        let mut con : CpuExecution = interrupts(program(&[0xA9, 0x00, 0xF0, 0x00, 0xEA]));
+        con.step();
+        con.step();
+        con.set_irq(true);
+        assert_eq!(con.step(), SystemState::CpuInst);
+        assert_eq!(con.rt_pc, 0x0205);
+        assert_eq!(con.step(), SystemState::CpuIrq);
+
+        // LDA #$00, BNE not taken, NOP
+        let mut con : CpuExecution = interrupts(program(&[0xA9, 0x00, 0xD0, 0x00, 0xEA]));
+        con.step();
+        con.step();
+        con.set_irq(true);
+        assert_eq!(con.step(), SystemState::CpuIrq);
+        assert_eq!(con.rt_pc, 0x0300);
+    }
+
+    // RAM whose NMI output goes up on the first write to the stack.
+    struct NmiOnPush
+    {
+        ram: Ram,
+        nmi: bool,
+    }
+
+    impl Bus for NmiOnPush
+    {
+        fn read(&mut self, addr: u16) -> u8
+        {
+            return self.ram.read(addr);
+        }
+
+        fn write(&mut self, addr: u16, data: u8)
+        {
+            if (0x0100..=0x01FF).contains(&addr)
+            {
+                self.nmi = true;
+            }
+            self.ram.write(addr, data);
+        }
+
+        fn nmi(&mut self) -> bool
+        {
+            return self.nmi;
+        }
+    }
+
+    // an NMI raised during BRK's pushes takes over the vector fetch, the
+    // status on the stack still says BRK and the NMI is not taken twice.
+    #[test]
+    fn nmi_hijacks_brk()
+    {
+        let mut ram : Ram = Ram::new();
+        ram.load(0x0200, &[0x00, 0x00]);
+        ram.load(0x0400, &[0xEA]);
+
+        let mut con : CpuExecution = interrupts(Box::new(NmiOnPush { ram, nmi: false }));
+        con.step();
+        assert_eq!(con.rt_pc, 0x0400);
+        assert_eq!(con.bus.peek(0x01FB) & StatusFlags::B.bits(), StatusFlags::B.bits());
+        assert_eq!(con.bus.peek(0x01FC), 0x02);
+
+        assert_eq!(con.step(), SystemState::CpuInst);
+        assert_eq!(con.rt_pc, 0x0401);
+    }
+
+    // run one instruction from $0200 and hand back every bus cycle it made.
+    fn bus_cycles(variant: CpuVariant, code: &[u8], x: u8, operand: (u16, u8)) -> Vec<(u16, u8, BusAccess)>
+    {
+        let mut ram : Ram = Ram::new();
+        ram.load(0x0200, code);
+        ram.load(operand.0, &[operand.1]);
+
+        let cycles : Rc<RefCell<Vec<(u16, u8, BusAccess)>>> = Rc::new(RefCell::new(Vec::new()));
+        let log = cycles.clone();
+
+        let mut con : CpuExecution = CpuExecution::with_variant(Box::new(ram), variant);
+        con.rt_pc = 0x0200;
+        con.rt_x = x;
+        con.cycle_hook = Some(Box::new(move |addr, data, access| log.borrow_mut().push((addr, data, access))));
+        con.step();
+
+        assert_eq!(con.clock_count, cycles.borrow().len() as u64);
+        return cycles.take();
+    }
+
+    // INC abs,X on NMOS: the read at the address before the high byte is
+    // fixed, then the old value written back ahead of the new one.
+    #[test]
+    fn inc_abs_x_bus_order_nmos()
+    {
+        use BusAccess::{Read, Write};
+
+        let same_page = bus_cycles(CpuVariant::Nmos6502, &[0xFE, 0x00, 0x10], 0x05, (0x1005, 0x41));
+        assert_eq!(same_page, vec![
+            (0x0200, 0xFE, Read), (0x0201, 0x00, Read), (0x0202, 0x10, Read),
+            (0x1005, 0x41, Read), (0x1005, 0x41, Read),
+            (0x1005, 0x41, Write), (0x1005, 0x42, Write),
+        ]);
+
+        let crossed = bus_cycles(CpuVariant::Nmos6502, &[0xFE, 0xFF, 0x10], 0x02, (0x1101, 0x7F));
+        assert_eq!(crossed, vec![
+            (0x0200, 0xFE, Read), (0x0201, 0xFF, Read), (0x0202, 0x10, Read),
+            (0x1001, 0x00, Read), (0x1101, 0x7F, Read),
+            (0x1101, 0x7F, Write), (0x1101, 0x80, Write),
+        ]);
+    }
+
+    // INC abs,X on the 65C02: the high operand byte is read again while the
+    // address is fixed, and the operand is read twice instead of written
+    // twice.
+    #[test]
+    fn inc_abs_x_bus_order_cmos()
+    {
+        use BusAccess::{Read, Write};
+
+        let crossed = bus_cycles(CpuVariant::Wdc65C02, &[0xFE, 0xFF, 0x10], 0x02, (0x1101, 0x7F));
+        assert_eq!(crossed, vec![
+            (0x0200, 0xFE, Read), (0x0201, 0xFF, Read), (0x0202, 0x10, Read),
+            (0x0202, 0x10, Read), (0x1101, 0x7F, Read),
+            (0x1101, 0x7F, Read), (0x1101, 0x80, Write),
+        ]);
+    }
+}
@@ -0,0 +1,178 @@
+use std::fmt;
+
+use crate::bus::Bus;
+use crate::cpu::CpuVariant;
+use crate::instruction::{AddrMode, InstructionType};
+
+// One decoded instruction of a listing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line
+{
+    pub addr: u16,
+    pub bytes: Vec<u8>,           // opcode and operand as they sit in memory
+    pub mnemonic: &'static str,
+    pub operand: String,          // in the usual syntax, e.g. ($20),Y
+    pub documented: bool,
+    pub target: Option<u16>,      // where a branch, JMP or JSR goes
+}
+
+// $C000  B1 20     LDA ($20),Y
+// $C002  04 10    *NOP $10
+// undocumented opcodes get a * in front of the mnemonic.
+impl fmt::Display for Line
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        let raw : Vec<String> = self.bytes.iter().map(|b| format!("{:02X}", b)).collect();
+        let mark : char = if self.documented { ' ' } else { '*' };
+
+        let text : String = format!("{}{} {}", mark, self.mnemonic, self.operand);
+        return write!(f, "${:04X}  {:<8} {}", self.addr, raw.join(" "), text.trim_end());
+    }
+}
+
+// decode the instruction at the start of bytes, which sits at addr. If the
+// slice ends before the operand does, the bytes left over come back as a
+// .byte line so nothing is lost from the listing. An empty slice has no
+// instruction in it and gives None. decode(), disassemble()
+// and disassemble_bus() read NMOS code, their _for versions take the variant.
+pub fn decode(bytes: &[u8], addr: u16) -> Option<Line>
+{
+    return decode_for(CpuVariant::Nmos6502, bytes, addr);
+}
+
+pub fn decode_for(variant: CpuVariant, bytes: &[u8], addr: u16) -> Option<Line>
+{
+    let opcode : u8 = *bytes.first()?;
+    let inst = &variant.instructions()[opcode as usize];
+    let length : usize = inst.length() as usize;
+
+    if bytes.len() < length
+    {
+        let operand : Vec<String> = bytes.iter().map(|b| format!("${:02X}", b)).collect();
+        return Some(Line
+        {
+            addr,
+            bytes: bytes.to_vec(),
+            mnemonic: ".byte",
+            operand: operand.join(","),
+            documented: true,
+            target: None,
+        });
+    }
+
+    let lo : u16 = if length > 1 { bytes[1] as u16 } else { 0 };
+    let hi : u16 = if length > 2 { bytes[2] as u16 } else { 0 };
+    let word : u16 = (hi << 8) | lo;
+
+    let mut target : Option<u16> = None;
+
+    let operand : String = match inst.mode
+    {
+        AddrMode::A => String::from("A"),
+        AddrMode::IMP | AddrMode::JAM => String::new(),
+        AddrMode::IMM => format!("#${:02X}", lo),
+        AddrMode::ZPG => format!("${:02X}", lo),
+        AddrMode::ZpgX => format!("${:02X},X", lo),
+        AddrMode::ZpgY => format!("${:02X},Y", lo),
+        AddrMode::IndX => format!("(${:02X},X)", lo),
+        AddrMode::IndY => format!("(${:02X}),Y", lo),
+        AddrMode::ZpgInd => format!("(${:02X})", lo),
+        AddrMode::ABS => format!("${:04X}", word),
+        AddrMode::AbsX => format!("${:04X},X", word),
+        AddrMode::AbsY => format!("${:04X},Y", word),
+        AddrMode::IND => format!("(${:04X})", word),
+        AddrMode::AbsIndX => format!("(${:04X},X)", word),
+        AddrMode::REL =>
+        {
+            // the offset counts from the byte after the branch.
+            let dest : u16 = addr.wrapping_add(2).wrapping_add(lo as u8 as i8 as u16);
+            target = Some(dest);
+            format!("${:04X}", dest)
+        }
+        AddrMode::ZpgRel =>
+        {
+            let dest : u16 = addr.wrapping_add(3).wrapping_add(hi as u8 as i8 as u16);
+            target = Some(dest);
+            format!("${:02X},${:04X}", lo, dest)
+        }
+    };
+
+    if matches!(inst.inst_type, InstructionType::JMP | InstructionType::JSR) && matches!(inst.mode, AddrMode::ABS)
+    {
+        target = Some(word);
+    }
+
+    return Some(Line
+    {
+        addr,
+        bytes: bytes[..length].to_vec(),
+        mnemonic: inst.mnemonic(),
+        operand,
+        documented: inst.documented,
+        target,
+    });
+}
+
+// disassemble a whole image that is loaded at origin.
+pub fn disassemble(bytes: &[u8], origin: u16) -> Vec<Line>
+{
+    return disassemble_for(CpuVariant::Nmos6502, bytes, origin);
+}
+
+pub fn disassemble_for(variant: CpuVariant, bytes: &[u8], origin: u16) -> Vec<Line>
+{
+    let mut lines : Vec<Line> = Vec::new();
+    let mut offset : usize = 0;
+
+    while let Some(line) = decode_for(variant, &bytes[offset..], origin.wrapping_add(offset as u16))
+    {
+        offset += line.bytes.len();
+        lines.push(line);
+    }
+
+    return lines;
+}
+
+// disassemble start..=end as the CPU would see it over the bus. The bytes
+// are peeked, so devices mapped in the range are left alone.
+pub fn disassemble_bus(bus: &mut dyn Bus, start: u16, end: u16) -> Vec<Line>
+{
+    return disassemble_bus_for(CpuVariant::Nmos6502, bus, start, end);
+}
+
+pub fn disassemble_bus_for(variant: CpuVariant, bus: &mut dyn Bus, start: u16, end: u16) -> Vec<Line>
+{
+    let mut bytes : Vec<u8> = Vec::new();
+    let mut addr : u16 = start;
+
+    loop
+    {
+        bytes.push(bus.peek(addr));
+        if addr == end
+        {
+            break;
+        }
+        addr = addr.wrapping_add(1);
+    }
+
+    return disassemble_for(variant, &bytes, start);
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn decode_short_input()
+    {
+        assert_eq!(decode(&[], 0x1000), None);
+
+        let line : Line = decode(&[0xAD, 0x34], 0x1000).unwrap();
+        assert_eq!(line.mnemonic, ".byte");
+        assert_eq!(line.operand, "$AD,$34");
+
+        assert!(disassemble(&[], 0x1000).is_empty());
+    }
+}
@@ -0,0 +1,150 @@
+use std::fmt;
+use std::ops::{BitAnd, BitOr, Not};
+
+// The processor status register, P. Bit 5 has nothing behind it and always
+// reads back as 1. B has no storage in the chip either, it only exists in
+// copies of P that have been pushed to the stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StatusFlags(u8);
+
+impl StatusFlags
+{
+    pub const C: StatusFlags = StatusFlags(1 << 0); // Carry
+    pub const Z: StatusFlags = StatusFlags(1 << 1); // Zero
+    pub const I: StatusFlags = StatusFlags(1 << 2); // Interrupt (IRQ disable)
+    pub const D: StatusFlags = StatusFlags(1 << 3); // Decimal (use BCD for arithmetics)
+    pub const B: StatusFlags = StatusFlags(1 << 4); // Break
+    pub const U: StatusFlags = StatusFlags(1 << 5); // Unused
+    pub const V: StatusFlags = StatusFlags(1 << 6); // Overflow
+    pub const N: StatusFlags = StatusFlags(1 << 7); // Negative
+
+    pub const fn empty() -> StatusFlags
+    {
+        return StatusFlags(0x00);
+    }
+
+    pub const fn from_bits(bits: u8) -> StatusFlags
+    {
+        return StatusFlags(bits);
+    }
+
+    pub const fn bits(self) -> u8
+    {
+        return self.0;
+    }
+
+    pub const fn contains(self, other: StatusFlags) -> bool
+    {
+        return (self.0 & other.0) == other.0;
+    }
+
+    pub fn insert(&mut self, other: StatusFlags)
+    {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: StatusFlags)
+    {
+        self.0 &= !other.0;
+    }
+
+    pub fn set(&mut self, other: StatusFlags, value: bool)
+    {
+        if value
+        {
+            self.insert(other);
+        }
+        else
+        {
+            self.remove(other);
+        }
+    }
+
+    // the carry as a number, ready to feed into an add or a rotate.
+    pub const fn carry(self) -> u8
+    {
+        return self.0 & StatusFlags::C.0;
+    }
+
+    // the byte PHP and BRK (brk = true) or IRQ and NMI (brk = false) push.
+    // The unused bit is always set, B tells the handler which one it was.
+    pub const fn pushed(self, brk: bool) -> u8
+    {
+        let bits : u8 = self.0 | StatusFlags::U.0;
+        if brk
+        {
+            return bits | StatusFlags::B.0;
+        }
+        return bits & !StatusFlags::B.0;
+    }
+
+    // the register after PLP or RTI pulls a byte, B is dropped and the
+    // unused bit reads back as 1 whatever was on the stack.
+    pub const fn pulled(byte: u8) -> StatusFlags
+    {
+        return StatusFlags((byte & !StatusFlags::B.0) | StatusFlags::U.0);
+    }
+}
+
+impl BitOr for StatusFlags
+{
+    type Output = StatusFlags;
+
+    fn bitor(self, rhs: StatusFlags) -> StatusFlags
+    {
+        return StatusFlags(self.0 | rhs.0);
+    }
+}
+
+impl BitAnd for StatusFlags
+{
+    type Output = StatusFlags;
+
+    fn bitand(self, rhs: StatusFlags) -> StatusFlags
+    {
+        return StatusFlags(self.0 & rhs.0);
+    }
+}
+
+impl Not for StatusFlags
+{
+    type Output = StatusFlags;
+
+    fn not(self) -> StatusFlags
+    {
+        return StatusFlags(!self.0);
+    }
+}
+
+impl From<u8> for StatusFlags
+{
+    fn from(bits: u8) -> StatusFlags
+    {
+        return StatusFlags(bits);
+    }
+}
+
+impl From<StatusFlags> for u8
+{
+    fn from(flags: StatusFlags) -> u8
+    {
+        return flags.0;
+    }
+}
+
+// NV-BDIZC, upper case for a set flag and lower case for a clear one.
+impl fmt::Display for StatusFlags
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        let names = [(StatusFlags::N, 'N'), (StatusFlags::V, 'V'), (StatusFlags::U, '-'), (StatusFlags::B, 'B'),
+                     (StatusFlags::D, 'D'), (StatusFlags::I, 'I'), (StatusFlags::Z, 'Z'), (StatusFlags::C, 'C')];
+
+        for (flag, name) in names
+        {
+            let shown : char = if self.contains(flag) { name } else { name.to_ascii_lowercase() };
+            write!(f, "{}", shown)?;
+        }
+        return Ok(());
+    }
+}
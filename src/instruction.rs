@@ -1,11 +1,14 @@
 use std::fmt::Debug;
 
+use crate::flags::StatusFlags;
+
 // Address modes 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AddrMode
 {
     A, // accumulator
     ABS, // absolute
+    AbsIndX, // absolute, x-indexed, indirect (65C02 JMP)
     AbsX, // absolute, x-indexed
     AbsY, // absolute, y-indexed
     IMM, // immediate
@@ -16,11 +19,13 @@ pub enum AddrMode
     JAM, // JAM
     REL, // relative
     ZPG, //zero page
+    ZpgInd, // zeropage, indirect (65C02)
+    ZpgRel, // zeropage, relative (65C02 BBR/BBS)
     ZpgX, // zeropage, x-indexed
     ZpgY // zeropage, y-indexed
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InstructionType
 {
     ADC, // add with carry
@@ -30,6 +35,22 @@ pub enum InstructionType
     ALR, // and oper + LSR
     ARR, // and oper + ROR
     ASL, // arithmetic shift left
+    BBR0, // branch on bit reset 0 (65C02)
+    BBR1, // branch on bit reset 1 (65C02)
+    BBR2, // branch on bit reset 2 (65C02)
+    BBR3, // branch on bit reset 3 (65C02)
+    BBR4, // branch on bit reset 4 (65C02)
+    BBR5, // branch on bit reset 5 (65C02)
+    BBR6, // branch on bit reset 6 (65C02)
+    BBR7, // branch on bit reset 7 (65C02)
+    BBS0, // branch on bit set 0 (65C02)
+    BBS1, // branch on bit set 1 (65C02)
+    BBS2, // branch on bit set 2 (65C02)
+    BBS3, // branch on bit set 3 (65C02)
+    BBS4, // branch on bit set 4 (65C02)
+    BBS5, // branch on bit set 5 (65C02)
+    BBS6, // branch on bit set 6 (65C02)
+    BBS7, // branch on bit set 7 (65C02)
     BCC, // branch on carry clear
     BCS, // branch on carry set
     BEQ, // branch on equal (zero set)
@@ -37,6 +58,7 @@ pub enum InstructionType
     BMI, // branch on minus (negative set)
     BNE, // branch on not equal (zero clear)
     BPL, // branch on plus (negative clear)
+    BRA, // branch always (65C02)
     BRK, // break / interrupt
     BVC, // branch on overflow clear
     BVS, // branch on overflow set
@@ -70,14 +92,26 @@ pub enum InstructionType
     ORA, // or with accumulator
     PHA, // push accumulator
     PHP, // push processor status (SR)
+    PHX, // push x (65C02)
+    PHY, // push y (65C02)
     PLA, // pull accumulator
     PLP, // pull processor status (SR)
+    PLX, // pull x (65C02)
+    PLY, // pull y (65C02)
     RRA, // ROR Oper + ADC oper
     RLA, // ROL oper + AND oper
     ROL, // rotate left
     ROR, // rotate right
     RTI, // return from interrupt
     RTS, // return from subroutine
+    RMB0, // reset memory bit 0 (65C02)
+    RMB1, // reset memory bit 1 (65C02)
+    RMB2, // reset memory bit 2 (65C02)
+    RMB3, // reset memory bit 3 (65C02)
+    RMB4, // reset memory bit 4 (65C02)
+    RMB5, // reset memory bit 5 (65C02)
+    RMB6, // reset memory bit 6 (65C02)
+    RMB7, // reset memory bit 7 (65C02)
     SAX, // (AXS, AAX)
     SBX, // (AXS, SAX)
     SBC, // subtract with carry
@@ -88,21 +122,34 @@ pub enum InstructionType
     SHX, // (A11, SXA, XAS)
     SHY, // (A11, SYA, SAY)
     SLO, // 
+    SMB0, // set memory bit 0 (65C02)
+    SMB1, // set memory bit 1 (65C02)
+    SMB2, // set memory bit 2 (65C02)
+    SMB3, // set memory bit 3 (65C02)
+    SMB4, // set memory bit 4 (65C02)
+    SMB5, // set memory bit 5 (65C02)
+    SMB6, // set memory bit 6 (65C02)
+    SMB7, // set memory bit 7 (65C02)
     SRE, // 
     STA, // store accumulator
+    STP, // stop the clock until reset (65C02)
     STX, // store x
     STY, // store y
+    STZ, // store zero (65C02)
     TAS, // (XAS, SHS)
     TAX, // transfer accumulator x
     TAY, // transfer accumulator y
+    TRB, // test and reset bits (65C02)
+    TSB, // test and set bits (65C02)
     TSX, // transfer stack pointer to x
     TXA, // transfer x to accumulator
     TXS, // transfer x to stack pointer
     TYA,// transfer y to accumulator
-    USBC // SBC oper + NOP
+    USBC, // SBC oper + NOP
+    WAI // wait for an interrupt (65C02)
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct Instruction
 {
     // instruction type
@@ -112,7 +159,15 @@ pub struct Instruction
    pub  mode: AddrMode,
     
     //CPU Cycles
-   pub  cycles: u8
+   pub  cycles: u8,
+
+    // part of the published instruction set, false for the undocumented
+    // opcodes including the extra NOPs and the JAMs
+    pub documented: bool,
+
+    // an entry of the 65C02 table, whose timing and flags differ in places
+    // from the NMOS opcode of the same name
+    pub cmos: bool
 }
 
 
@@ -120,291 +175,853 @@ pub struct Instruction
 pub const INSTRUCTIONS: [Instruction; 0x100] =
 [
     // 0x00 - 0xF0
-    Instruction {inst_type: InstructionType::BRK, mode: AddrMode::IMP, cycles: 7},
-    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::IndX, cycles: 6},
-    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 0},
-    Instruction {inst_type: InstructionType::SLO, mode: AddrMode::ZPG, cycles: 5},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ZPG, cycles: 3},
-    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::ZPG, cycles: 3},
-    Instruction {inst_type: InstructionType::ASL, mode: AddrMode::ZPG, cycles: 5},
-    Instruction {inst_type: InstructionType::SLO, mode: AddrMode::ZPG, cycles: 5},
-    Instruction {inst_type: InstructionType::PHP, mode: AddrMode::IMP, cycles: 3},
-    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::IMM, cycles: 2},
-    Instruction {inst_type: InstructionType::ASL, mode: AddrMode::A, cycles: 2},
-    Instruction {inst_type: InstructionType::ANC, mode: AddrMode::IMM, cycles: 2},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ABS, cycles: 4},
-    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::ABS, cycles: 4},
-    Instruction {inst_type: InstructionType::ASL, mode: AddrMode::ABS, cycles: 6},
-    Instruction {inst_type: InstructionType::SLO, mode: AddrMode::ABS, cycles: 6},
+    Instruction {inst_type: InstructionType::BRK, mode: AddrMode::IMP, cycles: 7, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::IndX, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 1, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::SLO, mode: AddrMode::IndX, cycles: 8, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ZPG, cycles: 3, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ASL, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::SLO, mode: AddrMode::ZPG, cycles: 5, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::PHP, mode: AddrMode::IMP, cycles: 3, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ASL, mode: AddrMode::A, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ANC, mode: AddrMode::IMM, cycles: 2, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ABS, cycles: 4, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ASL, mode: AddrMode::ABS, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::SLO, mode: AddrMode::ABS, cycles: 6, documented: false, cmos: false},
 
     // 0x10
-    Instruction {inst_type: InstructionType::BPL, mode: AddrMode::REL, cycles: 2},
-    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::IndY, cycles: 5},
-    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 0},
-    Instruction {inst_type: InstructionType::SLO, mode: AddrMode::IndY, cycles: 8},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ZpgX, cycles: 4},
-    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::ZpgX, cycles: 4},
-    Instruction {inst_type: InstructionType::ASL, mode: AddrMode::ZpgX, cycles: 6},
-    Instruction {inst_type: InstructionType::SLO, mode: AddrMode::ZpgX, cycles: 6},
-    Instruction {inst_type: InstructionType::CLC, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::AbsY, cycles: 4},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::SLO, mode: AddrMode::AbsY, cycles: 7},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::AbsX, cycles: 4},
-    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::AbsX, cycles: 4},
-    Instruction {inst_type: InstructionType::ASL, mode: AddrMode::AbsX, cycles: 7},
-    Instruction {inst_type: InstructionType::SLO, mode: AddrMode::AbsX, cycles: 7},
+    Instruction {inst_type: InstructionType::BPL, mode: AddrMode::REL, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::IndY, cycles: 5, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 1, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::SLO, mode: AddrMode::IndY, cycles: 8, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ZpgX, cycles: 4, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::ZpgX, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ASL, mode: AddrMode::ZpgX, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::SLO, mode: AddrMode::ZpgX, cycles: 6, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::CLC, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::AbsY, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 2, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::SLO, mode: AddrMode::AbsY, cycles: 7, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::AbsX, cycles: 4, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::AbsX, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ASL, mode: AddrMode::AbsX, cycles: 7, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::SLO, mode: AddrMode::AbsX, cycles: 7, documented: false, cmos: false},
 
     // 0x20
-    Instruction {inst_type: InstructionType::JSR, mode: AddrMode::ABS, cycles: 3},
-    Instruction {inst_type: InstructionType::AND, mode: AddrMode::IndX, cycles: 6},
-    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 0},
-    Instruction {inst_type: InstructionType::RLA, mode: AddrMode::IndX, cycles: 8},
-    Instruction {inst_type: InstructionType::BIT, mode: AddrMode::ZPG, cycles: 3},
-    Instruction {inst_type: InstructionType::AND, mode: AddrMode::ZPG, cycles: 3},
-    Instruction {inst_type: InstructionType::ROL, mode: AddrMode::ZPG, cycles: 5},
-    Instruction {inst_type: InstructionType::RLA, mode: AddrMode::ZPG, cycles: 5},
-    Instruction {inst_type: InstructionType::PLP, mode: AddrMode::IMP, cycles: 4},
-    Instruction {inst_type: InstructionType::AND, mode: AddrMode::IMM, cycles: 2},
-    Instruction {inst_type: InstructionType::ROL, mode: AddrMode::A, cycles: 2},
-    Instruction {inst_type: InstructionType::ANC, mode: AddrMode::IMM, cycles: 2},
-    Instruction {inst_type: InstructionType::BIT, mode: AddrMode::ABS, cycles: 4},
-    Instruction {inst_type: InstructionType::AND, mode: AddrMode::ABS, cycles: 4},
-    Instruction {inst_type: InstructionType::ROL, mode: AddrMode::ABS, cycles: 6},
-    Instruction {inst_type: InstructionType::RLA, mode: AddrMode::ABS, cycles: 6},
+    Instruction {inst_type: InstructionType::JSR, mode: AddrMode::ABS, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::AND, mode: AddrMode::IndX, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 1, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::RLA, mode: AddrMode::IndX, cycles: 8, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::BIT, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::AND, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ROL, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::RLA, mode: AddrMode::ZPG, cycles: 5, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::PLP, mode: AddrMode::IMP, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::AND, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ROL, mode: AddrMode::A, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ANC, mode: AddrMode::IMM, cycles: 2, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::BIT, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::AND, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ROL, mode: AddrMode::ABS, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::RLA, mode: AddrMode::ABS, cycles: 6, documented: false, cmos: false},
 
     // 0x30
-    Instruction {inst_type: InstructionType::BMI, mode: AddrMode::REL, cycles: 2},
-    Instruction {inst_type: InstructionType::AND, mode: AddrMode::IndY, cycles: 5},
-    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 0},
-    Instruction {inst_type: InstructionType::RLA, mode: AddrMode::IndY, cycles: 8},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ZpgX, cycles: 4},
-    Instruction {inst_type: InstructionType::AND, mode: AddrMode::ZpgX, cycles: 4},
-    Instruction {inst_type: InstructionType::ROL, mode: AddrMode::ZpgX, cycles: 6},
-    Instruction {inst_type: InstructionType::RLA, mode: AddrMode::ZpgX, cycles: 6},
-    Instruction {inst_type: InstructionType::SEC, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::AND, mode:AddrMode::AbsY, cycles: 4},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::RLA, mode: AddrMode::AbsY, cycles: 7},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::AbsX, cycles: 4},
-    Instruction {inst_type: InstructionType::AND, mode: AddrMode::AbsX, cycles: 4},
-    Instruction {inst_type: InstructionType::ROL, mode: AddrMode::AbsX, cycles: 7},
-    Instruction {inst_type: InstructionType::RLA, mode: AddrMode::AbsX, cycles: 7},
+    Instruction {inst_type: InstructionType::BMI, mode: AddrMode::REL, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::AND, mode: AddrMode::IndY, cycles: 5, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 1, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::RLA, mode: AddrMode::IndY, cycles: 8, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ZpgX, cycles: 4, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::AND, mode: AddrMode::ZpgX, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ROL, mode: AddrMode::ZpgX, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::RLA, mode: AddrMode::ZpgX, cycles: 6, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::SEC, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::AND, mode: AddrMode::AbsY, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 2, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::RLA, mode: AddrMode::AbsY, cycles: 7, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::AbsX, cycles: 4, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::AND, mode: AddrMode::AbsX, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ROL, mode: AddrMode::AbsX, cycles: 7, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::RLA, mode: AddrMode::AbsX, cycles: 7, documented: false, cmos: false},
 
     // 0x40
-    Instruction {inst_type: InstructionType::RTI, mode: AddrMode::IMP, cycles: 6},
-    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::IndX, cycles: 6},
-    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 0},
-    Instruction {inst_type: InstructionType::SRE, mode: AddrMode::IndX, cycles: 8},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ZPG, cycles: 4},
-    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::ZPG, cycles: 3},
-    Instruction {inst_type: InstructionType::LSR, mode: AddrMode::ZPG, cycles: 5},
-    Instruction {inst_type: InstructionType::SRE, mode: AddrMode::ZPG, cycles: 5},
-    Instruction {inst_type: InstructionType::PHA, mode: AddrMode::IMP, cycles: 3},
-    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::IMM, cycles: 2},
-    Instruction {inst_type: InstructionType::LSR, mode: AddrMode::A, cycles: 2},
-    Instruction {inst_type: InstructionType::ALR, mode: AddrMode::IMM, cycles: 2},
-    Instruction {inst_type: InstructionType::JMP, mode: AddrMode::ABS, cycles: 3},
-    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::ABS, cycles: 4},
-    Instruction {inst_type: InstructionType::LSR, mode: AddrMode::ABS, cycles: 6},
-    Instruction {inst_type: InstructionType::SRE, mode: AddrMode::ABS, cycles: 6},
+    Instruction {inst_type: InstructionType::RTI, mode: AddrMode::IMP, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::IndX, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 1, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::SRE, mode: AddrMode::IndX, cycles: 8, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ZPG, cycles: 3, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LSR, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::SRE, mode: AddrMode::ZPG, cycles: 5, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::PHA, mode: AddrMode::IMP, cycles: 3, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LSR, mode: AddrMode::A, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ALR, mode: AddrMode::IMM, cycles: 2, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::JMP, mode: AddrMode::ABS, cycles: 3, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LSR, mode: AddrMode::ABS, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::SRE, mode: AddrMode::ABS, cycles: 6, documented: false, cmos: false},
 
     // 0x50
-    Instruction {inst_type: InstructionType::BVC, mode: AddrMode::REL, cycles: 2},
-    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::IndY, cycles: 5},
-    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 0},
-    Instruction {inst_type: InstructionType::SRE, mode: AddrMode::IndY, cycles: 8},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ZpgX, cycles: 4},
-    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::ZpgX, cycles: 4},
-    Instruction {inst_type: InstructionType::LSR, mode: AddrMode::ZpgX, cycles: 6},
-    Instruction {inst_type: InstructionType::SRE, mode: AddrMode::ZpgX, cycles: 6},
-    Instruction {inst_type: InstructionType::CLI, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::AbsY, cycles: 4},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::SRE, mode: AddrMode::AbsY, cycles: 7},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::AbsX, cycles: 4},
-    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::AbsX, cycles: 4},
-    Instruction {inst_type: InstructionType::LSR, mode: AddrMode::AbsX, cycles: 7},
-    Instruction {inst_type: InstructionType::SRE, mode: AddrMode::AbsX, cycles: 7},
+    Instruction {inst_type: InstructionType::BVC, mode: AddrMode::REL, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::IndY, cycles: 5, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 1, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::SRE, mode: AddrMode::IndY, cycles: 8, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ZpgX, cycles: 4, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::ZpgX, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LSR, mode: AddrMode::ZpgX, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::SRE, mode: AddrMode::ZpgX, cycles: 6, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::CLI, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::AbsY, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 2, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::SRE, mode: AddrMode::AbsY, cycles: 7, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::AbsX, cycles: 4, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::AbsX, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LSR, mode: AddrMode::AbsX, cycles: 7, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::SRE, mode: AddrMode::AbsX, cycles: 7, documented: false, cmos: false},
 
     // 0x60
-    Instruction {inst_type: InstructionType::RTS, mode: AddrMode::IMP, cycles: 6},
-    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::IndX, cycles: 6},
-    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 0},
-    Instruction {inst_type: InstructionType::RRA, mode: AddrMode::IndX, cycles: 8},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ZPG, cycles: 4},
-    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::ZPG, cycles: 3},
-    Instruction {inst_type: InstructionType::ROR, mode: AddrMode::ZPG, cycles: 5},
-    Instruction {inst_type: InstructionType::RRA, mode: AddrMode::ZPG, cycles: 5},
-    Instruction {inst_type: InstructionType::PLA, mode: AddrMode::IMP, cycles: 4},
-    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::IMM, cycles: 2},
-    Instruction {inst_type: InstructionType::ROR, mode: AddrMode::A, cycles: 2},
-    Instruction {inst_type: InstructionType::ARR, mode: AddrMode::IMM, cycles: 2},
-    Instruction {inst_type: InstructionType::JMP, mode: AddrMode::IND, cycles: 5},
-    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::ABS, cycles: 4},
-    Instruction {inst_type: InstructionType::ROR, mode: AddrMode::ABS, cycles: 6},
-    Instruction {inst_type: InstructionType::RRA, mode: AddrMode::ABS, cycles: 6},
+    Instruction {inst_type: InstructionType::RTS, mode: AddrMode::IMP, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::IndX, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 1, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::RRA, mode: AddrMode::IndX, cycles: 8, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ZPG, cycles: 3, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ROR, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::RRA, mode: AddrMode::ZPG, cycles: 5, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::PLA, mode: AddrMode::IMP, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ROR, mode: AddrMode::A, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ARR, mode: AddrMode::IMM, cycles: 2, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::JMP, mode: AddrMode::IND, cycles: 5, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ROR, mode: AddrMode::ABS, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::RRA, mode: AddrMode::ABS, cycles: 6, documented: false, cmos: false},
 
     // 0x70
-    Instruction {inst_type: InstructionType::BVS, mode: AddrMode::REL, cycles: 2},
-    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::IndY, cycles: 5},
-    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 0},
-    Instruction {inst_type: InstructionType::RRA, mode: AddrMode::IndY, cycles: 8},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ZpgX, cycles: 4},
-    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::ZpgX, cycles: 4},
-    Instruction {inst_type: InstructionType::ROR, mode: AddrMode::ZpgX, cycles: 5},
-    Instruction {inst_type: InstructionType::RRA, mode: AddrMode::ZpgX, cycles: 6},
-    Instruction {inst_type: InstructionType::SEI, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::AbsY, cycles: 4},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles:2},
-    Instruction {inst_type: InstructionType::RRA, mode: AddrMode::AbsY, cycles: 7},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::AbsX, cycles: 4},
-    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::AbsX, cycles: 4},
-    Instruction {inst_type: InstructionType::ROR, mode: AddrMode::AbsX, cycles: 7},
-    Instruction {inst_type: InstructionType::RRA, mode: AddrMode::AbsX, cycles: 7},
+    Instruction {inst_type: InstructionType::BVS, mode: AddrMode::REL, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::IndY, cycles: 5, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 1, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::RRA, mode: AddrMode::IndY, cycles: 8, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ZpgX, cycles: 4, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::ZpgX, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ROR, mode: AddrMode::ZpgX, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::RRA, mode: AddrMode::ZpgX, cycles: 6, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::SEI, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::AbsY, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 2, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::RRA, mode: AddrMode::AbsY, cycles: 7, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::AbsX, cycles: 4, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::AbsX, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ROR, mode: AddrMode::AbsX, cycles: 7, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::RRA, mode: AddrMode::AbsX, cycles: 7, documented: false, cmos: false},
 
     // 0x80
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::STA, mode: AddrMode::IndX, cycles: 6},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMM, cycles: 2},
-    Instruction {inst_type: InstructionType::SAX, mode: AddrMode::IndX, cycles: 6},
-    Instruction {inst_type: InstructionType::STY, mode: AddrMode::ZPG, cycles: 3},
-    Instruction {inst_type: InstructionType::STA, mode: AddrMode::ZPG, cycles: 3},
-    Instruction {inst_type: InstructionType::STX, mode: AddrMode::ZPG, cycles: 3},
-    Instruction {inst_type: InstructionType::SAX, mode: AddrMode::ZPG, cycles: 3},
-    Instruction {inst_type: InstructionType::DEY, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMM, cycles: 2},
-    Instruction {inst_type: InstructionType::TXA, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::ANE, mode: AddrMode::IMM, cycles: 2},
-    Instruction {inst_type: InstructionType::STY, mode: AddrMode::ABS, cycles: 4},
-    Instruction {inst_type: InstructionType::STA, mode: AddrMode::ABS, cycles: 4},
-    Instruction {inst_type: InstructionType::STX, mode: AddrMode::ABS, cycles: 4},
-    Instruction {inst_type: InstructionType::SAX, mode: AddrMode::ABS, cycles: 4},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMM, cycles: 2, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::STA, mode: AddrMode::IndX, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMM, cycles: 2, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::SAX, mode: AddrMode::IndX, cycles: 6, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::STY, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::STA, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::STX, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::SAX, mode: AddrMode::ZPG, cycles: 3, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::DEY, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMM, cycles: 2, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::TXA, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ANE, mode: AddrMode::IMM, cycles: 2, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::STY, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::STA, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::STX, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::SAX, mode: AddrMode::ABS, cycles: 4, documented: false, cmos: false},
 
     // 0x90
-    Instruction {inst_type: InstructionType::BCC, mode: AddrMode::REL, cycles: 2},
-    Instruction {inst_type: InstructionType::STA, mode: AddrMode::IndY, cycles: 6},
-    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 0},
-    Instruction {inst_type: InstructionType::SHA, mode: AddrMode::IndY, cycles: 6},
-    Instruction {inst_type: InstructionType::STY, mode: AddrMode::ZpgX, cycles: 4},
-    Instruction {inst_type: InstructionType::STA, mode: AddrMode::ZpgX, cycles: 4},
-    Instruction {inst_type: InstructionType::STX, mode: AddrMode::ZpgY, cycles: 4},
-    Instruction {inst_type: InstructionType::SAX, mode: AddrMode::ZpgY, cycles: 4},
-    Instruction {inst_type: InstructionType::TYA, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::STA, mode: AddrMode::AbsY, cycles: 5},
-    Instruction {inst_type: InstructionType::TXS, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::TAS, mode: AddrMode::AbsY, cycles: 5},
-    Instruction {inst_type: InstructionType::SHY, mode: AddrMode::AbsX, cycles: 5},
-    Instruction {inst_type: InstructionType::STA, mode: AddrMode::AbsX, cycles: 5},
-    Instruction {inst_type: InstructionType::SHX, mode: AddrMode::AbsY, cycles: 5},
-    Instruction {inst_type: InstructionType::SHA, mode: AddrMode::AbsY, cycles: 5},
+    Instruction {inst_type: InstructionType::BCC, mode: AddrMode::REL, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::STA, mode: AddrMode::IndY, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 1, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::SHA, mode: AddrMode::IndY, cycles: 6, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::STY, mode: AddrMode::ZpgX, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::STA, mode: AddrMode::ZpgX, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::STX, mode: AddrMode::ZpgY, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::SAX, mode: AddrMode::ZpgY, cycles: 4, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::TYA, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::STA, mode: AddrMode::AbsY, cycles: 5, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::TXS, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::TAS, mode: AddrMode::AbsY, cycles: 5, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::SHY, mode: AddrMode::AbsX, cycles: 5, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::STA, mode: AddrMode::AbsX, cycles: 5, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::SHX, mode: AddrMode::AbsY, cycles: 5, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::SHA, mode: AddrMode::AbsY, cycles: 5, documented: false, cmos: false},
 
     // 0xA0
-    Instruction {inst_type: InstructionType::LDY, mode: AddrMode::IMM, cycles: 2},
-    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::IndX, cycles: 6},
-    Instruction {inst_type: InstructionType::LDX, mode: AddrMode::IMM, cycles: 2},
-    Instruction {inst_type: InstructionType::LAX, mode: AddrMode::IndX, cycles:6},
-    Instruction {inst_type: InstructionType::LDY, mode: AddrMode::ZPG, cycles: 3},
-    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::ZPG, cycles: 3},
-    Instruction {inst_type: InstructionType::LDX, mode: AddrMode::ZPG, cycles: 3},
-    Instruction {inst_type: InstructionType::LAX, mode: AddrMode::ZPG, cycles: 3},
-    Instruction {inst_type: InstructionType::TAY, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::IMM, cycles: 2},
-    Instruction {inst_type: InstructionType::TAX, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::LXA, mode: AddrMode::IMM, cycles: 2},
-    Instruction {inst_type: InstructionType::LDY, mode: AddrMode::ABS, cycles: 4},
-    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::ABS, cycles: 4},
-    Instruction {inst_type: InstructionType::LDX, mode: AddrMode::ABS, cycles: 4},
-    Instruction {inst_type: InstructionType::LAX, mode: AddrMode::ABS, cycles: 4},
+    Instruction {inst_type: InstructionType::LDY, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::IndX, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LDX, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LAX, mode: AddrMode::IndX, cycles: 6, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::LDY, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LDX, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LAX, mode: AddrMode::ZPG, cycles: 3, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::TAY, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::TAX, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LXA, mode: AddrMode::IMM, cycles: 2, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::LDY, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LDX, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LAX, mode: AddrMode::ABS, cycles: 4, documented: false, cmos: false},
 
     // 0xB0
-    Instruction {inst_type: InstructionType::BCS, mode: AddrMode::REL, cycles: 2},
-    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::IndY, cycles: 5},
-    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 0},
-    Instruction {inst_type: InstructionType::LAX, mode: AddrMode::IndY, cycles: 5},
-    Instruction {inst_type: InstructionType::LDY, mode: AddrMode::ZpgX, cycles: 4},
-    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::ZpgX, cycles: 4},
-    Instruction {inst_type: InstructionType::LDX, mode: AddrMode::ZPG, cycles: 4},
-    Instruction {inst_type: InstructionType::LAX, mode: AddrMode::ZpgY, cycles: 4},
-    Instruction {inst_type: InstructionType::CLV, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::AbsY, cycles: 4},
-    Instruction {inst_type: InstructionType::TSX, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::LAS, mode: AddrMode::AbsY, cycles: 4},
-    Instruction {inst_type: InstructionType::LDY, mode: AddrMode::AbsX, cycles: 4},
-    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::AbsX, cycles: 4},
-    Instruction {inst_type: InstructionType::LDX, mode: AddrMode::AbsY, cycles: 4},
-    Instruction {inst_type: InstructionType::LAX, mode: AddrMode::AbsY, cycles: 4},
+    Instruction {inst_type: InstructionType::BCS, mode: AddrMode::REL, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::IndY, cycles: 5, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 1, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::LAX, mode: AddrMode::IndY, cycles: 5, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::LDY, mode: AddrMode::ZpgX, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::ZpgX, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LDX, mode: AddrMode::ZpgY, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LAX, mode: AddrMode::ZpgY, cycles: 4, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::CLV, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::AbsY, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::TSX, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LAS, mode: AddrMode::AbsY, cycles: 4, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::LDY, mode: AddrMode::AbsX, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::AbsX, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LDX, mode: AddrMode::AbsY, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::LAX, mode: AddrMode::AbsY, cycles: 4, documented: false, cmos: false},
 
     // 0xC0
-    Instruction {inst_type: InstructionType::CPY, mode: AddrMode::IMM, cycles: 2},
-    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::IndX, cycles: 6},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMM, cycles: 2},
-    Instruction {inst_type: InstructionType::DCP, mode: AddrMode::IndX, cycles: 8},
-    Instruction {inst_type: InstructionType::CPY, mode: AddrMode::ZPG, cycles: 3},
-    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::ZPG, cycles: 3},
-    Instruction {inst_type: InstructionType::DEC, mode: AddrMode::ZPG, cycles: 5},
-    Instruction {inst_type: InstructionType::DCP, mode: AddrMode::ZPG, cycles: 5},
-    Instruction {inst_type: InstructionType::INY, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::IMM, cycles: 2},
-    Instruction {inst_type: InstructionType::DEX, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::SBX, mode: AddrMode::IMM, cycles: 2},
-    Instruction {inst_type: InstructionType::CPY, mode: AddrMode::ABS, cycles: 4},
-    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::ABS, cycles: 4},
-    Instruction {inst_type: InstructionType::DEC, mode: AddrMode::ABS, cycles: 6},
-    Instruction {inst_type: InstructionType::DCP, mode: AddrMode::ABS, cycles: 6},
+    Instruction {inst_type: InstructionType::CPY, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::IndX, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMM, cycles: 2, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::DCP, mode: AddrMode::IndX, cycles: 8, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::CPY, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::DEC, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::DCP, mode: AddrMode::ZPG, cycles: 5, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::INY, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::DEX, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::SBX, mode: AddrMode::IMM, cycles: 2, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::CPY, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::DEC, mode: AddrMode::ABS, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::DCP, mode: AddrMode::ABS, cycles: 6, documented: false, cmos: false},
 
     // 0xD0
-    Instruction {inst_type: InstructionType::BNE, mode: AddrMode::REL, cycles: 2},
-    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::IndY, cycles: 5},
-    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 0},
-    Instruction {inst_type: InstructionType::DCP, mode: AddrMode::IndY, cycles: 8},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ZpgX, cycles: 4},
-    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::ZpgX, cycles: 4},
-    Instruction {inst_type: InstructionType::DEC, mode: AddrMode::ZpgX, cycles: 6},
-    Instruction {inst_type: InstructionType::DCP, mode: AddrMode::ZpgX, cycles: 6},
-    Instruction {inst_type: InstructionType::CLD, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::AbsY, cycles: 4},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::DCP, mode: AddrMode::AbsY, cycles: 7},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::AbsX, cycles: 4},
-    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::AbsX, cycles: 4},
-    Instruction {inst_type: InstructionType::DEC, mode: AddrMode::AbsX, cycles: 7},
-    Instruction {inst_type: InstructionType::DCP, mode: AddrMode::AbsX, cycles: 7},
+    Instruction {inst_type: InstructionType::BNE, mode: AddrMode::REL, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::IndY, cycles: 5, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 1, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::DCP, mode: AddrMode::IndY, cycles: 8, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ZpgX, cycles: 4, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::ZpgX, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::DEC, mode: AddrMode::ZpgX, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::DCP, mode: AddrMode::ZpgX, cycles: 6, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::CLD, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::AbsY, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 2, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::DCP, mode: AddrMode::AbsY, cycles: 7, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::AbsX, cycles: 4, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::AbsX, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::DEC, mode: AddrMode::AbsX, cycles: 7, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::DCP, mode: AddrMode::AbsX, cycles: 7, documented: false, cmos: false},
 
     // 0xE0
-    Instruction {inst_type: InstructionType::CPX, mode: AddrMode::IMM, cycles: 2},
-    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::IndX, cycles: 6},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMM, cycles: 2},
-    Instruction {inst_type: InstructionType::ISC, mode: AddrMode::IndX, cycles: 8},
-    Instruction {inst_type: InstructionType::CPX, mode: AddrMode::ZPG, cycles: 3},
-    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::ZPG, cycles: 3},
-    Instruction {inst_type: InstructionType::INC, mode: AddrMode::ZPG, cycles: 5},
-    Instruction {inst_type: InstructionType::ISC, mode: AddrMode::ZPG, cycles: 5},
-    Instruction {inst_type: InstructionType::INX, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::AbsY, cycles: 4},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::USBC, mode: AddrMode::IMM, cycles: 2},
-    Instruction {inst_type: InstructionType::CPX, mode: AddrMode::ABS, cycles: 4},
-    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::ABS, cycles: 4},
-    Instruction {inst_type: InstructionType::INC, mode: AddrMode::ABS , cycles: 6},
-    Instruction {inst_type: InstructionType::ISC, mode: AddrMode::ABS, cycles: 6},
+    Instruction {inst_type: InstructionType::CPX, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::IndX, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMM, cycles: 2, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::ISC, mode: AddrMode::IndX, cycles: 8, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::CPX, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::INC, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ISC, mode: AddrMode::ZPG, cycles: 5, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::INX, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::USBC, mode: AddrMode::IMM, cycles: 2, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::CPX, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::INC, mode: AddrMode::ABS, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ISC, mode: AddrMode::ABS, cycles: 6, documented: false, cmos: false},
     
     // 0xF0
-    Instruction {inst_type: InstructionType::BEQ, mode: AddrMode::REL, cycles: 2},
-    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::IndY, cycles: 5},
-    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 0},
-    Instruction {inst_type: InstructionType::ISC, mode: AddrMode::IndY, cycles: 8},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ZpgX, cycles: 4},
-    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::ZpgX, cycles: 4},
-    Instruction {inst_type: InstructionType::INC, mode: AddrMode::ZpgX, cycles: 6},
-    Instruction {inst_type: InstructionType::ISC, mode: AddrMode::ZpgX, cycles: 6},
-    Instruction {inst_type: InstructionType::SED, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::AbsY, cycles: 4},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 2},
-    Instruction {inst_type: InstructionType::ISC, mode: AddrMode::AbsY, cycles: 7},
-    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::AbsX, cycles: 4},
-    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::AbsX, cycles: 4},
-    Instruction {inst_type: InstructionType::INC, mode: AddrMode::AbsX, cycles: 7},
-    Instruction {inst_type: InstructionType::ISC, mode: AddrMode::AbsX, cycles: 7}
+    Instruction {inst_type: InstructionType::BEQ, mode: AddrMode::REL, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::IndY, cycles: 5, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::JAM, mode: AddrMode::JAM, cycles: 1, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::ISC, mode: AddrMode::IndY, cycles: 8, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ZpgX, cycles: 4, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::ZpgX, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::INC, mode: AddrMode::ZpgX, cycles: 6, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ISC, mode: AddrMode::ZpgX, cycles: 6, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::SED, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::AbsY, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 2, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::ISC, mode: AddrMode::AbsY, cycles: 7, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::AbsX, cycles: 4, documented: false, cmos: false},
+    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::AbsX, cycles: 4, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::INC, mode: AddrMode::AbsX, cycles: 7, documented: true, cmos: false},
+    Instruction {inst_type: InstructionType::ISC, mode: AddrMode::AbsX, cycles: 7, documented: false, cmos: false}
+
+];
+
+// The WDC 65C02, with the Rockwell bit instructions in columns 7 and F.
+// Every other opcode the NMOS part left undefined is a NOP here, most of
+// them one byte and one cycle long. Branches are listed without
+// the cycles for taking them, BRA included, and the abs,X shifts at 6
+// since a page cross costs them one more, as it does a read.
+pub const INSTRUCTIONS_65C02: [Instruction; 0x100] =
+[
+    // 0x00 - 0xF0
+    Instruction {inst_type: InstructionType::BRK, mode: AddrMode::IMP, cycles: 7, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::IndX, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMM, cycles: 2, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::TSB, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ASL, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::RMB0, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::PHP, mode: AddrMode::IMP, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ASL, mode: AddrMode::A, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::TSB, mode: AddrMode::ABS, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ASL, mode: AddrMode::ABS, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::BBR0, mode: AddrMode::ZpgRel, cycles: 5, documented: true, cmos: true},
+
+    // 0x10
+    Instruction {inst_type: InstructionType::BPL, mode: AddrMode::REL, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::IndY, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::ZpgInd, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::TRB, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::ZpgX, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ASL, mode: AddrMode::ZpgX, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::RMB1, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::CLC, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::AbsY, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::INC, mode: AddrMode::A, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::TRB, mode: AddrMode::ABS, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ORA, mode: AddrMode::AbsX, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ASL, mode: AddrMode::AbsX, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::BBR1, mode: AddrMode::ZpgRel, cycles: 5, documented: true, cmos: true},
+
+    // 0x20
+    Instruction {inst_type: InstructionType::JSR, mode: AddrMode::ABS, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::AND, mode: AddrMode::IndX, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMM, cycles: 2, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::BIT, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::AND, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ROL, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::RMB2, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::PLP, mode: AddrMode::IMP, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::AND, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ROL, mode: AddrMode::A, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::BIT, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::AND, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ROL, mode: AddrMode::ABS, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::BBR2, mode: AddrMode::ZpgRel, cycles: 5, documented: true, cmos: true},
+
+    // 0x30
+    Instruction {inst_type: InstructionType::BMI, mode: AddrMode::REL, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::AND, mode: AddrMode::IndY, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::AND, mode: AddrMode::ZpgInd, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::BIT, mode: AddrMode::ZpgX, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::AND, mode: AddrMode::ZpgX, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ROL, mode: AddrMode::ZpgX, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::RMB3, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::SEC, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::AND, mode: AddrMode::AbsY, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::DEC, mode: AddrMode::A, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::BIT, mode: AddrMode::AbsX, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::AND, mode: AddrMode::AbsX, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ROL, mode: AddrMode::AbsX, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::BBR3, mode: AddrMode::ZpgRel, cycles: 5, documented: true, cmos: true},
+
+    // 0x40
+    Instruction {inst_type: InstructionType::RTI, mode: AddrMode::IMP, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::IndX, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMM, cycles: 2, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ZPG, cycles: 3, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::LSR, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::RMB4, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::PHA, mode: AddrMode::IMP, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::LSR, mode: AddrMode::A, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::JMP, mode: AddrMode::ABS, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::LSR, mode: AddrMode::ABS, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::BBR4, mode: AddrMode::ZpgRel, cycles: 5, documented: true, cmos: true},
+
+    // 0x50
+    Instruction {inst_type: InstructionType::BVC, mode: AddrMode::REL, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::IndY, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::ZpgInd, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ZpgX, cycles: 4, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::ZpgX, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::LSR, mode: AddrMode::ZpgX, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::RMB5, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::CLI, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::AbsY, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::PHY, mode: AddrMode::IMP, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ABS, cycles: 8, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::EOR, mode: AddrMode::AbsX, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::LSR, mode: AddrMode::AbsX, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::BBR5, mode: AddrMode::ZpgRel, cycles: 5, documented: true, cmos: true},
+
+    // 0x60
+    Instruction {inst_type: InstructionType::RTS, mode: AddrMode::IMP, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::IndX, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMM, cycles: 2, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::STZ, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ROR, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::RMB6, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::PLA, mode: AddrMode::IMP, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ROR, mode: AddrMode::A, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::JMP, mode: AddrMode::IND, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ROR, mode: AddrMode::ABS, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::BBR6, mode: AddrMode::ZpgRel, cycles: 5, documented: true, cmos: true},
+
+    // 0x70
+    Instruction {inst_type: InstructionType::BVS, mode: AddrMode::REL, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::IndY, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::ZpgInd, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::STZ, mode: AddrMode::ZpgX, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::ZpgX, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ROR, mode: AddrMode::ZpgX, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::RMB7, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::SEI, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::AbsY, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::PLY, mode: AddrMode::IMP, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::JMP, mode: AddrMode::AbsIndX, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ADC, mode: AddrMode::AbsX, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::ROR, mode: AddrMode::AbsX, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::BBR7, mode: AddrMode::ZpgRel, cycles: 5, documented: true, cmos: true},
+
+    // 0x80
+    Instruction {inst_type: InstructionType::BRA, mode: AddrMode::REL, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::STA, mode: AddrMode::IndX, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMM, cycles: 2, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::STY, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::STA, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::STX, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::SMB0, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::DEY, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::BIT, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::TXA, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::STY, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::STA, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::STX, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::BBS0, mode: AddrMode::ZpgRel, cycles: 5, documented: true, cmos: true},
+
+    // 0x90
+    Instruction {inst_type: InstructionType::BCC, mode: AddrMode::REL, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::STA, mode: AddrMode::IndY, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::STA, mode: AddrMode::ZpgInd, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::STY, mode: AddrMode::ZpgX, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::STA, mode: AddrMode::ZpgX, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::STX, mode: AddrMode::ZpgY, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::SMB1, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::TYA, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::STA, mode: AddrMode::AbsY, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::TXS, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::STZ, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::STA, mode: AddrMode::AbsX, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::STZ, mode: AddrMode::AbsX, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::BBS1, mode: AddrMode::ZpgRel, cycles: 5, documented: true, cmos: true},
+
+    // 0xA0
+    Instruction {inst_type: InstructionType::LDY, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::IndX, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::LDX, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::LDY, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::LDX, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::SMB2, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::TAY, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::TAX, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::LDY, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::LDX, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::BBS2, mode: AddrMode::ZpgRel, cycles: 5, documented: true, cmos: true},
+
+    // 0xB0
+    Instruction {inst_type: InstructionType::BCS, mode: AddrMode::REL, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::IndY, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::ZpgInd, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::LDY, mode: AddrMode::ZpgX, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::ZpgX, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::LDX, mode: AddrMode::ZpgY, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::SMB3, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::CLV, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::AbsY, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::TSX, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::LDY, mode: AddrMode::AbsX, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::LDA, mode: AddrMode::AbsX, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::LDX, mode: AddrMode::AbsY, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::BBS3, mode: AddrMode::ZpgRel, cycles: 5, documented: true, cmos: true},
 
+    // 0xC0
+    Instruction {inst_type: InstructionType::CPY, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::IndX, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMM, cycles: 2, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::CPY, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::DEC, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::SMB4, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::INY, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::DEX, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::WAI, mode: AddrMode::IMP, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::CPY, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::DEC, mode: AddrMode::ABS, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::BBS4, mode: AddrMode::ZpgRel, cycles: 5, documented: true, cmos: true},
+
+    // 0xD0
+    Instruction {inst_type: InstructionType::BNE, mode: AddrMode::REL, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::IndY, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::ZpgInd, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ZpgX, cycles: 4, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::ZpgX, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::DEC, mode: AddrMode::ZpgX, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::SMB5, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::CLD, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::AbsY, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::PHX, mode: AddrMode::IMP, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::STP, mode: AddrMode::IMP, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ABS, cycles: 4, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::CMP, mode: AddrMode::AbsX, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::DEC, mode: AddrMode::AbsX, cycles: 7, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::BBS5, mode: AddrMode::ZpgRel, cycles: 5, documented: true, cmos: true},
+
+    // 0xE0
+    Instruction {inst_type: InstructionType::CPX, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::IndX, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMM, cycles: 2, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::CPX, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::ZPG, cycles: 3, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::INC, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::SMB6, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::INX, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::IMM, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::CPX, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::ABS, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::INC, mode: AddrMode::ABS, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::BBS6, mode: AddrMode::ZpgRel, cycles: 5, documented: true, cmos: true},
+
+    // 0xF0
+    Instruction {inst_type: InstructionType::BEQ, mode: AddrMode::REL, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::IndY, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::ZpgInd, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ZpgX, cycles: 4, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::ZpgX, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::INC, mode: AddrMode::ZpgX, cycles: 6, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::SMB7, mode: AddrMode::ZPG, cycles: 5, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::SED, mode: AddrMode::IMP, cycles: 2, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::AbsY, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::PLX, mode: AddrMode::IMP, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::IMP, cycles: 1, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::NOP, mode: AddrMode::ABS, cycles: 4, documented: false, cmos: true},
+    Instruction {inst_type: InstructionType::SBC, mode: AddrMode::AbsX, cycles: 4, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::INC, mode: AddrMode::AbsX, cycles: 7, documented: true, cmos: true},
+    Instruction {inst_type: InstructionType::BBS7, mode: AddrMode::ZpgRel, cycles: 5, documented: true, cmos: true}
 ];
+
+impl AddrMode
+{
+    // how many operand bytes follow the opcode.
+    pub const fn operand_len(&self) -> u8
+    {
+        return match self
+        {
+            AddrMode::A | AddrMode::IMP | AddrMode::JAM => 0,
+            AddrMode::IMM | AddrMode::ZPG | AddrMode::ZpgX | AddrMode::ZpgY
+                | AddrMode::IndX | AddrMode::IndY | AddrMode::ZpgInd | AddrMode::REL => 1,
+            AddrMode::ABS | AddrMode::AbsX | AddrMode::AbsY | AddrMode::IND | AddrMode::AbsIndX | AddrMode::ZpgRel => 2,
+        };
+    }
+}
+
+impl InstructionType
+{
+    pub const fn mnemonic(&self) -> &'static str
+    {
+        return match self
+        {
+            InstructionType::ADC => "ADC",
+            InstructionType::ANC => "ANC",
+            InstructionType::AND => "AND",
+            InstructionType::ANE => "ANE",
+            InstructionType::ALR => "ALR",
+            InstructionType::ARR => "ARR",
+            InstructionType::ASL => "ASL",
+            InstructionType::BBR0 => "BBR0",
+            InstructionType::BBR1 => "BBR1",
+            InstructionType::BBR2 => "BBR2",
+            InstructionType::BBR3 => "BBR3",
+            InstructionType::BBR4 => "BBR4",
+            InstructionType::BBR5 => "BBR5",
+            InstructionType::BBR6 => "BBR6",
+            InstructionType::BBR7 => "BBR7",
+            InstructionType::BBS0 => "BBS0",
+            InstructionType::BBS1 => "BBS1",
+            InstructionType::BBS2 => "BBS2",
+            InstructionType::BBS3 => "BBS3",
+            InstructionType::BBS4 => "BBS4",
+            InstructionType::BBS5 => "BBS5",
+            InstructionType::BBS6 => "BBS6",
+            InstructionType::BBS7 => "BBS7",
+            InstructionType::BCC => "BCC",
+            InstructionType::BCS => "BCS",
+            InstructionType::BEQ => "BEQ",
+            InstructionType::BIT => "BIT",
+            InstructionType::BMI => "BMI",
+            InstructionType::BNE => "BNE",
+            InstructionType::BPL => "BPL",
+            InstructionType::BRA => "BRA",
+            InstructionType::BRK => "BRK",
+            InstructionType::BVC => "BVC",
+            InstructionType::BVS => "BVS",
+            InstructionType::CLC => "CLC",
+            InstructionType::CLD => "CLD",
+            InstructionType::CLI => "CLI",
+            InstructionType::CLV => "CLV",
+            InstructionType::CMP => "CMP",
+            InstructionType::CPX => "CPX",
+            InstructionType::CPY => "CPY",
+            InstructionType::DCP => "DCP",
+            InstructionType::DEC => "DEC",
+            InstructionType::DEX => "DEX",
+            InstructionType::DEY => "DEY",
+            InstructionType::EOR => "EOR",
+            InstructionType::INC => "INC",
+            InstructionType::INX => "INX",
+            InstructionType::INY => "INY",
+            InstructionType::ISC => "ISC",
+            InstructionType::JAM => "JAM",
+            InstructionType::JMP => "JMP",
+            InstructionType::JSR => "JSR",
+            InstructionType::LAS => "LAS",
+            InstructionType::LAX => "LAX",
+            InstructionType::LDA => "LDA",
+            InstructionType::LDX => "LDX",
+            InstructionType::LDY => "LDY",
+            InstructionType::LSR => "LSR",
+            InstructionType::LXA => "LXA",
+            InstructionType::NOP => "NOP",
+            InstructionType::ORA => "ORA",
+            InstructionType::PHA => "PHA",
+            InstructionType::PHP => "PHP",
+            InstructionType::PHX => "PHX",
+            InstructionType::PHY => "PHY",
+            InstructionType::PLA => "PLA",
+            InstructionType::PLP => "PLP",
+            InstructionType::PLX => "PLX",
+            InstructionType::PLY => "PLY",
+            InstructionType::RRA => "RRA",
+            InstructionType::RLA => "RLA",
+            InstructionType::ROL => "ROL",
+            InstructionType::ROR => "ROR",
+            InstructionType::RTI => "RTI",
+            InstructionType::RTS => "RTS",
+            InstructionType::RMB0 => "RMB0",
+            InstructionType::RMB1 => "RMB1",
+            InstructionType::RMB2 => "RMB2",
+            InstructionType::RMB3 => "RMB3",
+            InstructionType::RMB4 => "RMB4",
+            InstructionType::RMB5 => "RMB5",
+            InstructionType::RMB6 => "RMB6",
+            InstructionType::RMB7 => "RMB7",
+            InstructionType::SAX => "SAX",
+            InstructionType::SBX => "SBX",
+            InstructionType::SBC => "SBC",
+            InstructionType::SEC => "SEC",
+            InstructionType::SED => "SED",
+            InstructionType::SEI => "SEI",
+            InstructionType::SHA => "SHA",
+            InstructionType::SHX => "SHX",
+            InstructionType::SHY => "SHY",
+            InstructionType::SLO => "SLO",
+            InstructionType::SMB0 => "SMB0",
+            InstructionType::SMB1 => "SMB1",
+            InstructionType::SMB2 => "SMB2",
+            InstructionType::SMB3 => "SMB3",
+            InstructionType::SMB4 => "SMB4",
+            InstructionType::SMB5 => "SMB5",
+            InstructionType::SMB6 => "SMB6",
+            InstructionType::SMB7 => "SMB7",
+            InstructionType::SRE => "SRE",
+            InstructionType::STA => "STA",
+            InstructionType::STP => "STP",
+            InstructionType::STX => "STX",
+            InstructionType::STY => "STY",
+            InstructionType::STZ => "STZ",
+            InstructionType::TAS => "TAS",
+            InstructionType::TAX => "TAX",
+            InstructionType::TAY => "TAY",
+            InstructionType::TRB => "TRB",
+            InstructionType::TSB => "TSB",
+            InstructionType::TSX => "TSX",
+            InstructionType::TXA => "TXA",
+            InstructionType::TXS => "TXS",
+            InstructionType::TYA => "TYA",
+            // $EB does exactly what SBC #imm does, it goes by the same name.
+            InstructionType::USBC => "SBC",
+            InstructionType::WAI => "WAI",
+        };
+    }
+}
+
+impl Instruction
+{
+    // total size in bytes, opcode included. BRK is counted as one byte even
+    // though the return address it pushes skips the byte after it.
+    pub const fn length(&self) -> u8
+    {
+        return 1 + self.mode.operand_len();
+    }
+
+    pub const fn mnemonic(&self) -> &'static str
+    {
+        return self.inst_type.mnemonic();
+    }
+
+    // true when crossing a page adds a cycle on top of `cycles`. Only the
+    // indexed reads do this, stores and read-modify-writes always take the
+    // long path. A branch pays it only when taken, on top of the cycle for
+    // taking it. The 65C02 lets its abs,X shifts off with 6 cycles when the
+    // page stays the same, so for it they count as reads.
+    pub const fn page_penalty(&self) -> bool
+    {
+        let shifts : bool = matches!(self.inst_type,
+            InstructionType::ASL | InstructionType::LSR | InstructionType::ROL | InstructionType::ROR);
+
+        let reads : bool = (shifts && self.cmos) || matches!(self.inst_type,
+            InstructionType::ADC | InstructionType::AND | InstructionType::BIT | InstructionType::CMP | InstructionType::EOR
+            | InstructionType::LAS | InstructionType::LAX | InstructionType::LDA | InstructionType::LDX
+            | InstructionType::LDY | InstructionType::NOP | InstructionType::ORA | InstructionType::SBC);
+
+        return match self.mode
+        {
+            AddrMode::AbsX | AddrMode::AbsY | AddrMode::IndY => reads,
+            AddrMode::REL | AddrMode::ZpgRel => true,
+            _ => false,
+        };
+    }
+
+    // the status flags whose value changes what the instruction does. PHP
+    // and BRK count as reading all of them since they push the register.
+    pub const fn flags_read(&self) -> StatusFlags
+    {
+        let all : StatusFlags = StatusFlags::from_bits(0xCF);
+
+        return match self.inst_type
+        {
+            InstructionType::ADC | InstructionType::SBC | InstructionType::USBC
+                | InstructionType::RRA | InstructionType::ISC | InstructionType::ARR =>
+                StatusFlags::from_bits(StatusFlags::C.bits() | StatusFlags::D.bits()),
+            InstructionType::ROL | InstructionType::ROR | InstructionType::RLA
+                | InstructionType::BCC | InstructionType::BCS => StatusFlags::C,
+            InstructionType::BEQ | InstructionType::BNE => StatusFlags::Z,
+            InstructionType::BMI | InstructionType::BPL => StatusFlags::N,
+            InstructionType::BVC | InstructionType::BVS => StatusFlags::V,
+            InstructionType::PHP | InstructionType::BRK => all,
+            _ => StatusFlags::empty(),
+        };
+    }
+
+    // the status flags the instruction may change. B and the unused bit are
+    // never in here, they have no storage in the register.
+    pub const fn flags_written(&self) -> StatusFlags
+    {
+        let nz : u8 = StatusFlags::N.bits() | StatusFlags::Z.bits();
+        let nzc : u8 = nz | StatusFlags::C.bits();
+
+        let bits : u8 = match self.inst_type
+        {
+            InstructionType::ADC | InstructionType::SBC | InstructionType::USBC
+                | InstructionType::RRA | InstructionType::ISC | InstructionType::ARR => nzc | StatusFlags::V.bits(),
+            InstructionType::ASL | InstructionType::LSR | InstructionType::ROL | InstructionType::ROR
+                | InstructionType::SLO | InstructionType::SRE | InstructionType::RLA
+                | InstructionType::ANC | InstructionType::ALR | InstructionType::SBX
+                | InstructionType::CMP | InstructionType::CPX | InstructionType::CPY | InstructionType::DCP => nzc,
+            InstructionType::AND | InstructionType::ORA | InstructionType::EOR
+                | InstructionType::LDA | InstructionType::LDX | InstructionType::LDY
+                | InstructionType::LAX | InstructionType::LAS | InstructionType::LXA | InstructionType::ANE
+                | InstructionType::TAX | InstructionType::TAY | InstructionType::TXA | InstructionType::TYA
+                | InstructionType::TSX | InstructionType::PLA | InstructionType::PLX | InstructionType::PLY
+                | InstructionType::INC | InstructionType::INX | InstructionType::INY
+                | InstructionType::DEC | InstructionType::DEX | InstructionType::DEY => nz,
+            // BIT #imm has no memory operand to take N and V from.
+            InstructionType::BIT if matches!(self.mode, AddrMode::IMM) => StatusFlags::Z.bits(),
+            InstructionType::BIT => nz | StatusFlags::V.bits(),
+            InstructionType::TRB | InstructionType::TSB => StatusFlags::Z.bits(),
+            InstructionType::CLC | InstructionType::SEC => StatusFlags::C.bits(),
+            InstructionType::CLD | InstructionType::SED => StatusFlags::D.bits(),
+            // the 65C02 also leaves decimal mode on the way into the handler.
+            InstructionType::BRK if self.cmos => StatusFlags::I.bits() | StatusFlags::D.bits(),
+            InstructionType::CLI | InstructionType::SEI | InstructionType::BRK => StatusFlags::I.bits(),
+            InstructionType::CLV => StatusFlags::V.bits(),
+            InstructionType::PLP | InstructionType::RTI => 0xCF,
+            _ => 0x00,
+        };
+
+        return StatusFlags::from_bits(bits);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn brk_flags()
+    {
+        assert_eq!(INSTRUCTIONS[0x00].flags_written(), StatusFlags::I);
+        assert_eq!(INSTRUCTIONS_65C02[0x00].flags_written(), StatusFlags::I | StatusFlags::D);
+    }
+
+    // ASL abs,X: 7 cycles flat on NMOS, 6 plus one for a page cross on the 65C02.
+    #[test]
+    fn shift_page_penalty()
+    {
+        assert!(!INSTRUCTIONS[0x1E].page_penalty());
+        assert!(INSTRUCTIONS_65C02[0x1E].page_penalty());
+        assert!(!INSTRUCTIONS_65C02[0x0E].page_penalty());
+        assert!(!INSTRUCTIONS_65C02[0xFE].page_penalty());
+    }
+}
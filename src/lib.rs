@@ -0,0 +1,19 @@
+// explicit returns are the house style throughout the core.
+#![allow(clippy::needless_return)]
+
+pub mod asm;
+pub mod bus;
+pub mod cpu;
+pub mod cpuproc;
+pub mod disasm;
+pub mod flags;
+pub mod instruction;
+pub mod port;
+pub mod trace;
+
+pub use bus::{Bus, BusAccess, CycleHook, Device, MemoryMap, Ram, Region, RegionKind, RomWrite};
+pub use cpu::{CpuVariant, SystemState};
+pub use cpuproc::CpuExecution as Cpu;
+pub use flags::StatusFlags;
+pub use instruction::{AddrMode, Instruction, InstructionType, INSTRUCTIONS, INSTRUCTIONS_65C02};
+pub use port::{IoPort, PortRead, PortWrite};
@@ -1,33 +1,132 @@
-use cpuproc::CpuExecution;
+#![allow(clippy::needless_return)]
 
+use std::env;
+use std::fs;
+use std::process;
 
-pub mod cpu;
-#[path = "cpuproc.rs"] pub mod cpuproc; 
-#[path = "instruction.rs"] pub mod instruction; 
+use cpu6502::{asm, disasm};
+use cpu6502::{Cpu, CpuVariant, Ram};
 
 
-fn main() {
+// CPU6502 disasm [--65c02] <file> [origin]
+// origin is hex, with or without a leading $ or 0x, and defaults to $0000.
+fn disasm_command(args: &[String])
+{
+    let (variant, args) = variant_option(args);
 
-    let mut con: CpuExecution = CpuExecution
+    if args.is_empty() || args.len() > 2
     {
-        fetch: 0x00,
-        temp: 0x0000,
-        addr_abs: 0x0000,
-        addr_rel: 0x00,
-        opcode: 0x00,
-        cycles: 0,
-        clock_count: 0,
+        eprintln!("usage: CPU6502 disasm [--65c02] <file> [origin]");
+        process::exit(2);
+    }
 
-        rt_pc: 0x00, // Program Counter Registers
-        rt_ac: 0x00, // Accumulator Register
-        rt_x: 0x00, // Index Register X
-        rt_y: 0x00, // Index Register Y
-        rt_sr: 0x00, // Status Register
-        rt_sp: 0x00, // Stack Pointer
-        rt_none: 0x00
+    let image : Vec<u8> = match fs::read(&args[0])
+    {
+        Ok(image) => image,
+        Err(err) =>
+        {
+            eprintln!("{}: {}", args[0], err);
+            process::exit(1);
+        }
+    };
 
+    let origin : u16 = match args.get(1)
+    {
+        Some(text) => match parse_hex(text)
+        {
+            Some(origin) => origin,
+            None =>
+            {
+                eprintln!("bad origin: {}", text);
+                process::exit(2);
+            }
+        },
+        None => 0x0000,
     };
 
+    for line in disasm::disassemble_for(variant, &image, origin)
+    {
+        println!("{}", line);
+    }
+}
+
+// CPU6502 asm [--65c02] <source> <output>
+// writes one flat image covering everything the source puts in memory.
+fn asm_command(args: &[String])
+{
+    let (variant, args) = variant_option(args);
+
+    if args.len() != 2
+    {
+        eprintln!("usage: CPU6502 asm [--65c02] <source> <output>");
+        process::exit(2);
+    }
+
+    let source : String = match fs::read_to_string(&args[0])
+    {
+        Ok(source) => source,
+        Err(err) =>
+        {
+            eprintln!("{}: {}", args[0], err);
+            process::exit(1);
+        }
+    };
+
+    let assembly : asm::Assembly = match asm::assemble_for(variant, &source)
+    {
+        Ok(assembly) => assembly,
+        Err(err) =>
+        {
+            eprintln!("{}: {}", args[0], err);
+            process::exit(1);
+        }
+    };
+
+    let (origin, image) = assembly.image();
+    if let Err(err) = fs::write(&args[1], &image)
+    {
+        eprintln!("{}: {}", args[1], err);
+        process::exit(1);
+    }
+
+    println!("${:04X}, {} bytes", origin, image.len());
+}
+
+// a leading --65c02 picks the CMOS instruction set, NMOS otherwise.
+fn variant_option(args: &[String]) -> (CpuVariant, &[String])
+{
+    if args.first().map(|arg| arg.as_str()) == Some("--65c02")
+    {
+        return (CpuVariant::Wdc65C02, &args[1..]);
+    }
+    return (CpuVariant::Nmos6502, args);
+}
+
+fn parse_hex(text: &str) -> Option<u16>
+{
+    let digits : &str = text.trim_start_matches('$').trim_start_matches("0x");
+    return u16::from_str_radix(digits, 16).ok();
+}
+
+fn main() {
+
+    let args : Vec<String> = env::args().collect();
+
+    if args.len() > 1 && args[1] == "disasm"
+    {
+        disasm_command(&args[2..]);
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "asm"
+    {
+        asm_command(&args[2..]);
+        return;
+    }
+
+    let mut con: Cpu = Cpu::new(Box::new(Ram::new()));
+    con.reset();
+
     println!("Hello, world!");
-    print!("{:?} \n", cpu::process_instruction(0, & mut con));
+    println!("{:?}", con.step());
 }
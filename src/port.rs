@@ -0,0 +1,243 @@
+// The I/O port built into the 6510 and 8500. The data direction register
+// sits at $0000 and the port itself at $0001, a 1 in the direction register
+// makes that bit an output. On the C64 bits 0-2 pick the memory
+// configuration and bits 3-5 run the cassette.
+//
+// A bit set to input reads whatever the board puts on the pin. Bits the
+// board leaves floating keep the last level the port drove onto them for a
+// while, the pin capacitance holds it, and then fade to 0.
+
+// called with the pin levels after every write to $0000 or $0001, and on
+// reset.
+pub type PortWrite = Box<dyn FnMut(u8)>;
+
+// the levels the board holds the input pins at.
+pub type PortRead = Box<dyn FnMut() -> u8>;
+
+// roughly how long a floating pin holds a 1 on a 6510, an 8500 holds it
+// several times longer.
+pub const FADE_CYCLES : u64 = 350_000;
+
+pub struct IoPort
+{
+    pub ddr: u8,          // $0000, 1 = output
+    pub data: u8,         // $0001 as last written, the output latch
+    pub driven: u8,       // input bits the board holds at a level, the rest float
+    pub fade_cycles: u64, // how long a floating bit keeps a 1
+
+    pub on_write: Option<PortWrite>,
+    pub on_read: Option<PortRead>, // without it every driven bit reads 1, as with pull-ups
+
+    charge: u8,           // floating bits still holding a 1
+    charged_at: [u64; 8], // clock count when each bit was last driven high
+}
+
+impl IoPort
+{
+    pub fn new() -> IoPort
+    {
+        return IoPort
+        {
+            ddr: 0x00,
+            data: 0x00,
+            driven: 0x00,
+            fade_cycles: FADE_CYCLES,
+
+            on_write: None,
+            on_read: None,
+
+            charge: 0x00,
+            charged_at: [0; 8],
+        };
+    }
+
+    // reset turns every bit into an input, the latch keeps its value. The
+    // bits it lets go start to fade from now and the board hears about it,
+    // just as with a store of 0 to $0000.
+    pub fn reset(&mut self, now: u64)
+    {
+        let released : u8 = self.ddr;
+        self.ddr = 0x00;
+        self.settle(released, now);
+    }
+
+    // the level on each pin at clock count now, outputs from the latch and
+    // inputs from the board or what is left of the charge.
+    pub fn pins(&mut self, now: u64) -> u8
+    {
+        for bit in 0..8
+        {
+            if now.saturating_sub(self.charged_at[bit]) >= self.fade_cycles
+            {
+                self.charge &= !(1 << bit);
+            }
+        }
+
+        let board : u8 = match self.on_read.as_mut()
+        {
+            Some(read) => read(),
+            None => 0xFF,
+        };
+        let inputs : u8 = (board & self.driven) | (self.charge & !self.driven);
+
+        return (self.data & self.ddr) | (inputs & !self.ddr);
+    }
+
+    // what the CPU reads at $0000 or $0001.
+    pub fn read(&mut self, addr: u16, now: u64) -> u8
+    {
+        return match addr
+        {
+            0x0000 => self.ddr,
+            _ => self.pins(now),
+        };
+    }
+
+    // a store to $0000 or $0001. Every output charges its pin to the level
+    // it drives, and so does a bit just turned from output into input, its
+    // charge only starts to fade from here.
+    pub fn write(&mut self, addr: u16, data: u8, now: u64)
+    {
+        let mut released : u8 = 0x00;
+        match addr
+        {
+            0x0000 =>
+            {
+                released = self.ddr & !data;
+                self.ddr = data;
+            }
+            _ => self.data = data,
+        }
+
+        self.settle(released, now);
+    }
+
+    // charge every output and every bit in released to the level of the
+    // latch, then hand the pins to the board.
+    fn settle(&mut self, released: u8, now: u64)
+    {
+        for bit in 0..8
+        {
+            let mask : u8 = 1 << bit;
+            if (self.ddr | released) & mask != 0
+            {
+                self.charge = (self.charge & !mask) | (self.data & mask);
+                self.charged_at[bit] = now;
+            }
+        }
+
+        let pins : u8 = self.pins(now);
+        if let Some(write) = self.on_write.as_mut()
+        {
+            write(pins);
+        }
+    }
+}
+
+impl Default for IoPort
+{
+    fn default() -> IoPort
+    {
+        return IoPort::new();
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::bus::{BusAccess, Ram};
+    use crate::cpu::CpuVariant;
+    use crate::cpuproc::CpuExecution;
+
+    // drive every bit high, wait well past the fade time, then turn the
+    // port into inputs. The pins were driven until that last store, so
+    // they still read 1 and only fade from there.
+    #[test]
+    fn fade_starts_when_output_is_released()
+    {
+        let mut ram : Ram = Ram::new();
+        ram.load(0x0200, &[
+            0xA9, 0xFF,       // LDA #$FF
+            0x85, 0x00,       // STA $00
+            0x85, 0x01,       // STA $01
+            0xA0, 0x00,       // LDY #$00
+            0xA2, 0x00,       // LDX #$00
+            0xCA,             // DEX
+            0xEA,             // NOP
+            0xEA,             // NOP
+            0xD0, 0xFB,       // BNE DEX
+            0x88,             // DEY
+            0xD0, 0xF6,       // BNE LDX
+            0xA9, 0x00,       // LDA #$00
+            0x85, 0x00,       // STA $00
+            0xA5, 0x01,       // LDA $01
+        ]);
+
+        let mut con : CpuExecution = CpuExecution::with_variant(Box::new(ram), CpuVariant::Mos6510);
+        con.rt_pc = 0x0200;
+        while con.rt_pc != 0x0218
+        {
+            con.step();
+        }
+
+        assert!(con.clock_count > FADE_CYCLES + 200_000);
+        assert_eq!(con.rt_ac, 0xFF);
+
+        let now : u64 = con.clock_count + FADE_CYCLES;
+        assert_eq!(con.port.as_mut().expect("6510 has a port").pins(now), 0x00);
+    }
+
+    // reset lets go of the outputs the same way STA $00 with 0 does.
+    #[test]
+    fn reset_starts_the_fade()
+    {
+        let later : u64 = 2 * FADE_CYCLES;
+
+        let mut stored : IoPort = IoPort::new();
+        stored.write(0x0000, 0xFF, 0);
+        stored.write(0x0001, 0xFF, 0);
+        stored.write(0x0000, 0x00, later);
+        assert_eq!(stored.pins(later), 0xFF);
+
+        let heard : Rc<RefCell<Vec<u8>>> = Rc::new(RefCell::new(Vec::new()));
+        let log = heard.clone();
+
+        let mut reset : IoPort = IoPort::new();
+        reset.write(0x0000, 0xFF, 0);
+        reset.write(0x0001, 0xFF, 0);
+        reset.on_write = Some(Box::new(move |pins| log.borrow_mut().push(pins)));
+        reset.reset(later);
+        assert_eq!(reset.pins(later), 0xFF);
+        assert_eq!(reset.pins(later + FADE_CYCLES), 0x00);
+        assert_eq!(*heard.borrow(), vec![0xFF]);
+    }
+
+    // the board rebanks on the port reset before the CPU goes for the
+    // reset vector, so the vector comes from whatever is mapped in then.
+    #[test]
+    fn reset_rebanks_before_the_vector()
+    {
+        let events : Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+        let banking = events.clone();
+        let reads = events.clone();
+
+        let mut con : CpuExecution = CpuExecution::with_variant(Box::new(Ram::new()), CpuVariant::Mos6510);
+        let port : &mut IoPort = con.port.as_mut().expect("6510 has a port");
+        port.write(0x0000, 0x07, 0);
+        port.driven = 0x07;
+        port.on_write = Some(Box::new(move |pins| banking.borrow_mut().push(format!("bank {:02X}", pins))));
+        con.cycle_hook = Some(Box::new(move |addr, _, access|
+        {
+            if access == BusAccess::Read
+            {
+                reads.borrow_mut().push(format!("read {:04X}", addr));
+            }
+        }));
+
+        con.reset();
+        assert_eq!(*events.borrow(), vec!["bank 07", "read FFFC", "read FFFD"]);
+    }
+}
@@ -0,0 +1,177 @@
+use crate::cpu::CpuVariant;
+use crate::cpuproc::CpuExecution;
+use crate::disasm;
+use crate::instruction::{AddrMode, InstructionType};
+
+// One line per instruction in the layout of nestest.log, so a run can be
+// diffed straight against the reference log:
+//
+// C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 21 CYC:7
+// D959  B1 89     LDA ($89),Y = 0300 @ 0300 = 89  A:00 X:00 Y:00 P:26 SP:FB PPU: 78,168 CYC:...
+// C6BD  04 A9    *NOP $A9 = 00                    A:AA X:97 Y:4E P:EF SP:F5 PPU: 99,240 CYC:...
+//
+// The line describes the instruction about to run and the registers before
+// it runs. Memory operands show what is there now, looked at with peek so
+// the trace does not disturb any device. There is no PPU in here, its
+// position is worked out from the cycle count at three dots per cycle on
+// an NTSC frame of 262 lines of 341 dots.
+pub fn trace_line(con: &mut CpuExecution) -> String
+{
+    let pc : u16 = con.rt_pc;
+    let bytes : [u8; 3] = [con.bus.peek(pc), con.bus.peek(pc.wrapping_add(1)), con.bus.peek(pc.wrapping_add(2))];
+
+    let line : disasm::Line = disasm::decode_for(con.variant, &bytes, pc).expect("the opcode is always there");
+    let inst = &con.variant.instructions()[bytes[0] as usize];
+
+    let raw : Vec<String> = line.bytes.iter().map(|b| format!("{:02X}", b)).collect();
+    let mark : char = if line.documented { ' ' } else { '*' };
+
+    // nestest calls ISC by its other name.
+    let mnemonic : &str = match inst.inst_type
+    {
+        InstructionType::ISC => "ISB",
+        _ => line.mnemonic,
+    };
+
+    let operand : String = annotate(con, &line, inst.mode, inst.inst_type);
+    let text : String = format!("{} {}", mnemonic, operand);
+
+    // cycles still in flight, like those of the reset sequence, have gone
+    // by before the instruction starts, step adds them first as well.
+    let cycle : u64 = con.clock_count.saturating_add(con.cycles as u64);
+
+    // wide enough that a clock count stuck at u64::MAX still has a position.
+    let dots : u128 = cycle as u128 * 3;
+    let scanline : u128 = (dots / 341) % 262;
+    let dot : u128 = dots % 341;
+
+    return format!("{:04X}  {:<9}{}{:<32}A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X} PPU:{:>3},{:>3} CYC:{}",
+        pc, raw.join(" "), mark, text.trim_end(),
+        con.rt_ac, con.rt_x, con.rt_y, con.rt_sr.bits(), con.rt_sp,
+        scanline, dot, cycle);
+}
+
+// the operand as the disassembler prints it plus the address it ends up at
+// and the value there, the way nestest shows them.
+fn annotate(con: &mut CpuExecution, line: &disasm::Line, mode: AddrMode, inst_type: InstructionType) -> String
+{
+    let lo : u8 = line.bytes.get(1).copied().unwrap_or(0);
+    let hi : u8 = line.bytes.get(2).copied().unwrap_or(0);
+    let word : u16 = u16::from_le_bytes([lo, hi]);
+    let operand : &str = &line.operand;
+
+    return match mode
+    {
+        AddrMode::A | AddrMode::IMP | AddrMode::JAM | AddrMode::IMM | AddrMode::REL => operand.to_string(),
+        AddrMode::ZpgRel =>
+        {
+            format!("{} = {:02X}", operand, con.bus.peek(lo as u16))
+        }
+        AddrMode::ABS if matches!(inst_type, InstructionType::JMP | InstructionType::JSR) => operand.to_string(),
+        AddrMode::ZPG | AddrMode::ABS =>
+        {
+            format!("{} = {:02X}", operand, con.bus.peek(word))
+        }
+        AddrMode::ZpgX | AddrMode::ZpgY =>
+        {
+            let index : u8 = if mode == AddrMode::ZpgX { con.rt_x } else { con.rt_y };
+            let addr : u8 = lo.wrapping_add(index);
+            format!("{} @ {:02X} = {:02X}", operand, addr, con.bus.peek(addr as u16))
+        }
+        AddrMode::AbsX | AddrMode::AbsY =>
+        {
+            let index : u8 = if mode == AddrMode::AbsX { con.rt_x } else { con.rt_y };
+            let addr : u16 = word.wrapping_add(index as u16);
+            format!("{} @ {:04X} = {:02X}", operand, addr, con.bus.peek(addr))
+        }
+        AddrMode::IND =>
+        {
+            // the pointer's high byte comes from the same page, as on the
+            // NMOS chip. The 65C02 carries into the next one.
+            let high : u16 = match con.variant
+            {
+                CpuVariant::Wdc65C02 => word.wrapping_add(1),
+                _ => (word & 0xFF00) | (word.wrapping_add(1) & 0x00FF),
+            };
+            let dest : u16 = u16::from_le_bytes([con.bus.peek(word), con.bus.peek(high)]);
+            format!("{} = {:04X}", operand, dest)
+        }
+        AddrMode::AbsIndX =>
+        {
+            let ptr : u16 = word.wrapping_add(con.rt_x as u16);
+            let dest : u16 = u16::from_le_bytes([con.bus.peek(ptr), con.bus.peek(ptr.wrapping_add(1))]);
+            format!("{} @ {:04X} = {:04X}", operand, ptr, dest)
+        }
+        AddrMode::ZpgInd =>
+        {
+            let addr : u16 = zp_pointer(con, lo);
+            format!("{} = {:04X} = {:02X}", operand, addr, con.bus.peek(addr))
+        }
+        AddrMode::IndX =>
+        {
+            let ptr : u8 = lo.wrapping_add(con.rt_x);
+            let addr : u16 = zp_pointer(con, ptr);
+            format!("{} @ {:02X} = {:04X} = {:02X}", operand, ptr, addr, con.bus.peek(addr))
+        }
+        AddrMode::IndY =>
+        {
+            let base : u16 = zp_pointer(con, lo);
+            let addr : u16 = base.wrapping_add(con.rt_y as u16);
+            format!("{} = {:04X} @ {:04X} = {:02X}", operand, base, addr, con.bus.peek(addr))
+        }
+    };
+}
+
+// a pointer in zero page, the high byte wraps round to $00.
+fn zp_pointer(con: &mut CpuExecution, ptr: u8) -> u16
+{
+    return u16::from_le_bytes([con.bus.peek(ptr as u16), con.bus.peek(ptr.wrapping_add(1) as u16)]);
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use std::cell::RefCell;
+    use std::io;
+    use std::rc::Rc;
+    use crate::bus::Ram;
+
+    // collects what the CPU writes to its trace.
+    struct Shared(Rc<RefCell<Vec<u8>>>);
+
+    impl io::Write for Shared
+    {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize>
+        {
+            self.0.borrow_mut().extend_from_slice(buf);
+            return Ok(buf.len());
+        }
+
+        fn flush(&mut self) -> io::Result<()>
+        {
+            return Ok(());
+        }
+    }
+
+    // straight after reset both ways of getting the line agree with the
+    // first line of nestest.log.
+    #[test]
+    fn first_line_after_reset()
+    {
+        let mut ram : Ram = Ram::new();
+        ram.load(0xFFFC, &[0x00, 0xC0]);
+        ram.load(0xC000, &[0x4C, 0xF5, 0xC5]);
+
+        let mut con : CpuExecution = CpuExecution::new(Box::new(ram));
+        con.reset();
+
+        let expected : &str = "C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 21 CYC:7";
+        assert_eq!(con.trace_line(), expected);
+
+        let out : Rc<RefCell<Vec<u8>>> = Rc::new(RefCell::new(Vec::new()));
+        con.trace = Some(Box::new(Shared(out.clone())));
+        con.step();
+        assert_eq!(String::from_utf8(out.borrow().clone()).unwrap(), format!("{}\n", expected));
+    }
+}
@@ -0,0 +1,87 @@
+// explicit returns are the house style, tests included.
+#![allow(clippy::needless_return)]
+
+// Klaus Dormann's 6502 functional test. The image is not shipped with the
+// repository, build it from https://github.com/Klaus2m5/6502_65C02_functional_tests
+// or take the prebuilt bin_files/6502_functional_test.bin and either drop it
+// in tests/roms/ or point KLAUS_FUNCTIONAL_TEST at it. Without it the test
+// is skipped.
+//
+// The test is a 64 KiB image started at $0400. Every check that fails, and
+// the final success, ends in a jump or branch to itself, so the run is over
+// as soon as an instruction leaves PC where it was. Which address that is
+// tells you the outcome: $3469 for success in the prebuilt image, or
+// whatever KLAUS_FUNCTIONAL_SUCCESS says for a build of your own.
+
+use std::env;
+use std::fs;
+
+use cpu6502::{Cpu, Ram, SystemState};
+
+const DEFAULT_IMAGE : &str = "tests/roms/6502_functional_test.bin";
+const DEFAULT_SUCCESS : u16 = 0x3469;
+const START : u16 = 0x0400;
+
+// the real thing takes about 30 million instructions.
+const MAX_STEPS : u64 = 100_000_000;
+
+#[test]
+fn klaus_functional_test()
+{
+    let path : String = env::var("KLAUS_FUNCTIONAL_TEST").unwrap_or(String::from(DEFAULT_IMAGE));
+
+    let image : Vec<u8> = match fs::read(&path)
+    {
+        Ok(image) => image,
+        Err(_) =>
+        {
+            eprintln!("skipping, {} not found", path);
+            return;
+        }
+    };
+    assert_eq!(image.len(), 0x10000, "{} is not a 64 KiB image", path);
+
+    let success : u16 = match env::var("KLAUS_FUNCTIONAL_SUCCESS")
+    {
+        Ok(text) => u16::from_str_radix(text.trim_start_matches('$'), 16).expect("bad KLAUS_FUNCTIONAL_SUCCESS"),
+        Err(_) => DEFAULT_SUCCESS,
+    };
+
+    let mut ram : Ram = Ram::new();
+    ram.load(0x0000, &image);
+
+    let mut cpu : Cpu = Cpu::new(Box::new(ram));
+    cpu.reset();
+    cpu.rt_pc = START;
+
+    let (trap, steps) = run_to_trap(&mut cpu);
+
+    // the test keeps the number of the check it is on at $0200.
+    let test_case : u8 = cpu.bus.peek(0x0200);
+
+    assert_eq!(trap, success,
+        "trapped at ${:04X} after {} instructions, test case ${:02X}, A:{:02X} X:{:02X} Y:{:02X} P:{} SP:{:02X}",
+        trap, steps, test_case, cpu.rt_ac, cpu.rt_x, cpu.rt_y, cpu.rt_sr, cpu.rt_sp);
+}
+
+// step until an instruction leaves PC unchanged and return where that was.
+fn run_to_trap(cpu: &mut Cpu) -> (u16, u64)
+{
+    for steps in 0..MAX_STEPS
+    {
+        let pc : u16 = cpu.rt_pc;
+        let state : SystemState = cpu.step();
+
+        if let SystemState::Jam { pc, opcode } = state
+        {
+            panic!("JAM ${:02X} at ${:04X} after {} instructions", opcode, pc, steps);
+        }
+
+        if cpu.rt_pc == pc
+        {
+            return (pc, steps);
+        }
+    }
+
+    panic!("no trap after {} instructions, PC ${:04X}", MAX_STEPS, cpu.rt_pc);
+}
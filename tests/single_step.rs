@@ -0,0 +1,258 @@
+// explicit returns are the house style, tests included.
+#![allow(clippy::needless_return)]
+
+// Runs the per-opcode JSON vectors from SingleStepTests (the 6502 set of
+// https://github.com/SingleStepTests/65x02, formerly ProcessorTests). Each
+// file, 00.json to ff.json, holds thousands of cases of one opcode:
+//
+//   { "name": "a9 2c 5a",
+//     "initial": { "pc": .., "s": .., "a": .., "x": .., "y": .., "p": .., "ram": [[addr, value], ..] },
+//     "final":   { same fields },
+//     "cycles":  [[addr, value, "read" | "write"], ..] }
+//
+// The vectors are not shipped with the repository. Put the 6502 directory's
+// json files in tests/single_step/ or point SINGLE_STEP_TESTS at them,
+// without them the test is skipped. SINGLE_STEP_IGNORE takes a comma
+// separated list of fields to leave out of the comparison (pc, s, a, x, y,
+// p, ram, cycles, bus) and SINGLE_STEP_LIMIT caps the cases run per opcode.
+//
+// The JAM opcodes are left out. The vectors have the chip going on reading
+// the bus in a pattern of its own, the core charges the opcode fetch and
+// then, under clock(), reads $FFFF on every cycle, and under step() makes
+// no more cycles at all.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use serde_json::Value;
+
+use cpu6502::{AddrMode, Bus, Cpu, StatusFlags, SystemState, INSTRUCTIONS};
+
+const DEFAULT_DIR : &str = "tests/single_step";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Access
+{
+    Read,
+    Write,
+}
+
+struct Memory
+{
+    mem: Vec<u8>,
+    log: Vec<(u16, u8, Access)>,
+}
+
+// flat RAM that remembers every access the CPU makes.
+struct LoggingBus
+{
+    shared: Rc<RefCell<Memory>>,
+}
+
+impl Bus for LoggingBus
+{
+    fn read(&mut self, addr: u16) -> u8
+    {
+        let mut memory = self.shared.borrow_mut();
+        let data : u8 = memory.mem[addr as usize];
+        memory.log.push((addr, data, Access::Read));
+        return data;
+    }
+
+    fn write(&mut self, addr: u16, data: u8)
+    {
+        let mut memory = self.shared.borrow_mut();
+        memory.mem[addr as usize] = data;
+        memory.log.push((addr, data, Access::Write));
+    }
+
+    fn peek(&mut self, addr: u16) -> u8
+    {
+        return self.shared.borrow().mem[addr as usize];
+    }
+}
+
+// mismatches for one opcode, per field: how many and the first one seen.
+#[derive(Default)]
+struct Report
+{
+    fields: BTreeMap<&'static str, (usize, String)>,
+}
+
+impl Report
+{
+    fn add(&mut self, field: &'static str, detail: String)
+    {
+        let entry = self.fields.entry(field).or_insert((0, detail));
+        entry.0 += 1;
+    }
+}
+
+#[test]
+fn single_step_tests()
+{
+    let dir : PathBuf = PathBuf::from(env::var("SINGLE_STEP_TESTS").unwrap_or(String::from(DEFAULT_DIR)));
+    if !dir.is_dir()
+    {
+        eprintln!("skipping, {} not found", dir.display());
+        return;
+    }
+
+    let ignore : Vec<String> = env::var("SINGLE_STEP_IGNORE").unwrap_or_default()
+        .split(',').map(|f| f.trim().to_string()).filter(|f| !f.is_empty()).collect();
+    let limit : usize = env::var("SINGLE_STEP_LIMIT").ok().and_then(|n| n.parse().ok()).unwrap_or(usize::MAX);
+
+    let shared : Rc<RefCell<Memory>> = Rc::new(RefCell::new(Memory { mem: vec![0x00; 0x10000], log: Vec::new() }));
+    let mut cpu : Cpu = Cpu::new(Box::new(LoggingBus { shared: shared.clone() }));
+
+    let mut failed : Vec<String> = Vec::new();
+    let mut missing : Vec<String> = Vec::new();
+    let mut ran : usize = 0;
+
+    for (opcode, inst) in INSTRUCTIONS.iter().enumerate()
+    {
+        if inst.mode == AddrMode::JAM
+        {
+            continue;
+        }
+
+        let path : PathBuf = dir.join(format!("{:02x}.json", opcode));
+        let text : String = match fs::read_to_string(&path)
+        {
+            Ok(text) => text,
+            Err(_) =>
+            {
+                missing.push(format!("{:02X}", opcode));
+                continue;
+            }
+        };
+        let cases : Vec<Value> = serde_json::from_str(&text).unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+
+        let mut report : Report = Report::default();
+        for case in cases.iter().take(limit)
+        {
+            run_case(&mut cpu, &shared, case, &mut report);
+            ran += 1;
+        }
+
+        for (field, (count, first)) in report.fields.iter()
+        {
+            if !ignore.iter().any(|f| f == field)
+            {
+                failed.push(format!("{:02X} {:<4} {:>3}: {} wrong, first {}",
+                    opcode, inst.mnemonic(), field, count, first));
+            }
+        }
+    }
+
+    if !missing.is_empty()
+    {
+        eprintln!("no vectors for {}", missing.join(" "));
+    }
+    eprintln!("{} cases run", ran);
+    assert!(failed.is_empty(), "\n{}", failed.join("\n"));
+}
+
+fn run_case(cpu: &mut Cpu, shared: &Rc<RefCell<Memory>>, case: &Value, report: &mut Report)
+{
+    let name : &str = case["name"].as_str().unwrap_or("?");
+    let initial : &Value = &case["initial"];
+    let expect : &Value = &case["final"];
+
+    {
+        let mut memory = shared.borrow_mut();
+        for (addr, data) in ram(initial)
+        {
+            memory.mem[addr as usize] = data;
+        }
+        memory.log.clear();
+    }
+
+    cpu.rt_pc = field(initial, "pc") as u16;
+    cpu.rt_sp = field(initial, "s") as u8;
+    cpu.rt_ac = field(initial, "a") as u8;
+    cpu.rt_x = field(initial, "x") as u8;
+    cpu.rt_y = field(initial, "y") as u8;
+    cpu.rt_sr = StatusFlags::from_bits(field(initial, "p") as u8);
+    cpu.cycles = 0;
+    cpu.irq_inhibit = true;
+    cpu.irq_delay = false;
+    cpu.nmi_pending = false;
+    cpu.state = SystemState::CpuInst;
+
+    let start : u64 = cpu.clock_count;
+    cpu.step();
+    let cycles : u64 = cpu.clock_count - start;
+
+    let registers : [(&'static str, u64); 6] = [
+        ("pc", cpu.rt_pc as u64), ("s", cpu.rt_sp as u64), ("a", cpu.rt_ac as u64),
+        ("x", cpu.rt_x as u64), ("y", cpu.rt_y as u64), ("p", cpu.rt_sr.bits() as u64),
+    ];
+    for (name_of, got) in registers
+    {
+        let want : u64 = field(expect, name_of);
+        if got != want
+        {
+            report.add(name_of, format!("{}: {} is {:02X}, want {:02X}", name, name_of, got, want));
+        }
+    }
+
+    let mut memory = shared.borrow_mut();
+
+    for (addr, want) in ram(expect)
+    {
+        let got : u8 = memory.mem[addr as usize];
+        if got != want
+        {
+            report.add("ram", format!("{}: ${:04X} is {:02X}, want {:02X}", name, addr, got, want));
+            break;
+        }
+    }
+
+    let bus : Vec<(u16, u8, Access)> = case["cycles"].as_array().map(|c| c.iter().map(access).collect()).unwrap_or_default();
+    if cycles as usize != bus.len()
+    {
+        report.add("cycles", format!("{}: took {}, want {}", name, cycles, bus.len()));
+    }
+    if memory.log != bus
+    {
+        report.add("bus", format!("{}: got {}, want {}", name, show(&memory.log), show(&bus)));
+    }
+
+    // leave memory zeroed for the next case.
+    for (addr, _) in ram(initial).into_iter().chain(ram(expect))
+    {
+        memory.mem[addr as usize] = 0x00;
+    }
+}
+
+fn field(state: &Value, name: &str) -> u64
+{
+    return state[name].as_u64().unwrap_or_else(|| panic!("missing {}", name));
+}
+
+fn ram(state: &Value) -> Vec<(u16, u8)>
+{
+    let entries : &Vec<Value> = state["ram"].as_array().expect("missing ram");
+    return entries.iter().map(|e| (e[0].as_u64().unwrap_or(0) as u16, e[1].as_u64().unwrap_or(0) as u8)).collect();
+}
+
+fn access(entry: &Value) -> (u16, u8, Access)
+{
+    let kind : Access = if entry[2].as_str() == Some("write") { Access::Write } else { Access::Read };
+    return (entry[0].as_u64().unwrap_or(0) as u16, entry[1].as_u64().unwrap_or(0) as u8, kind);
+}
+
+fn show(log: &[(u16, u8, Access)]) -> String
+{
+    let parts : Vec<String> = log.iter().map(|(addr, data, kind)|
+    {
+        let rw : char = if *kind == Access::Write { 'w' } else { 'r' };
+        return format!("{}{:04X}={:02X}", rw, addr, data);
+    }).collect();
+    return parts.join(" ");
+}